@@ -12,3 +12,17 @@ void hello_world(void);
 char *test_circuit(void);
 
 int32_t cat(int32_t a, int32_t b);
+
+void free_string(char *s);
+
+char *elgamal_setup(void);
+
+char *elgamal_keygen(const char *parameters_hex);
+
+bool elgamal_public_key_is_valid(const char *pk_hex);
+
+char *hex_to_base64(const char *s);
+
+char *base64_to_hex(const char *s);
+
+char *groth16_batch_verify(const char *vk_hex, const char *instances_json);
@@ -0,0 +1,445 @@
+use crate::gadget::{
+    hashes::{
+        self, constraints::CRHSchemeGadget, mimc7, mimc7::constraints::MiMCGadget, CRHScheme,
+    },
+    merkle_tree::{
+        constraints::PathVar,
+        mimc::{constraints::MiMCMerkleTreeConfigGadget, MiMCMerkleTreeConfig},
+        MerkleTree, Path,
+    },
+    public_encryptions::elgamal::{
+        self,
+        constraints::{scalar_mul_le, SecretKeyVar},
+    },
+    range_check::constraints::enforce_less_than,
+    symmetric_encrytions::symmetric::{self, constraints::SymmetricEncryptionSchemeGadget},
+};
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_crypto_primitives::Error;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::ToConstraintFieldGadget;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::AllocVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_std::marker::PhantomData;
+
+pub type CiphertextTreeConfig<F> = MiMCMerkleTreeConfig<F>;
+pub type CiphertextTreeConfigVar<F> = MiMCMerkleTreeConfigGadget<F>;
+
+/// Number of bits of a chunk index and of the number of chunks of a file.
+pub const CHUNK_INDEX_BITS: usize = 32;
+
+/// Splits `data` into chunks of `chunk_size` blocks, padding the last chunk with zero blocks.
+pub fn chunk_blocks<F: PrimeField>(data: &[F], chunk_size: usize) -> Vec<Vec<F>> {
+    data.chunks(chunk_size)
+        .map(|chunk| {
+            let mut chunk = chunk.to_vec();
+            chunk.resize(chunk_size, F::zero());
+            chunk
+        })
+        .collect()
+}
+
+/// Returns the Merkle tree whose leaves are the chunks of `chunk_size` ciphertext blocks. Its root,
+/// bound to the number of chunks by [`ciphertext_hash`], replaces the linear `h_ct = MiMC(ct_data)`
+/// of [`crate::datatrade::circuit::Registerdata`], so that each chunk can be registered by its own
+/// [`RegisterChunk`] proof.
+///
+/// The plaintext is expected to be padded with [`chunk_blocks`] before encryption, so `ct_data`
+/// must consist of whole chunks.
+pub fn ciphertext_tree<F: PrimeField + Absorb>(
+    rc: &mimc7::Parameters<F>,
+    ct_data: &[F],
+    chunk_size: usize,
+) -> Result<MerkleTree<CiphertextTreeConfig<F>>, Error> {
+    if chunk_size == 0
+        || ct_data.is_empty()
+        || !ct_data.chunks_exact(chunk_size).remainder().is_empty()
+    {
+        return Err("the ciphertext does not consist of whole chunks".into());
+    }
+    MerkleTree::new(rc, rc, ct_data.chunks(chunk_size))
+}
+
+/// Returns the registered `h_ct = MiMC(root || num_chunks || ct_r)` of a chunked file. Binding the
+/// number of chunks keeps a truncated file from verifying against the registration, and binding
+/// the randomness `ct_r` makes every chunk proof use the one `ct_r` that decrypts the file.
+pub fn ciphertext_hash<F: PrimeField + Absorb>(
+    rc: &mimc7::Parameters<F>,
+    root: F,
+    num_chunks: usize,
+    ct_r: F,
+) -> Result<F, Error> {
+    mimc7::MiMC::<F>::evaluate(rc, [root, F::from(num_chunks as u64), ct_r].to_vec())
+}
+
+/// Registers the chunk `chunk_index` of a file: proves that `ct_chunk = SE.Enc(data_chunk, k_data)`
+/// is the leaf at `chunk_index < num_chunks` of the ciphertext tree that `h_ct` commits to, as
+/// computed by [`ciphertext_hash`], together with the key
+/// statements of [`crate::datatrade::circuit::Registerdata`]. Blocks are encrypted under the pad
+/// of their index in the whole file, `chunk_index * chunk_size + j`. Every chunk of a file has the same
+/// circuit, so all its proofs share one verifying key.
+///
+/// The public inputs are, in order, `h_k_data`, `pk_peer_own`, `h_ct`, `num_chunks` and
+/// `chunk_index`; a file is registered once a proof verifies for every index below `num_chunks`.
+#[allow(non_snake_case)]
+#[derive(Clone)]
+pub struct RegisterChunk<C: CurveGroup, GG: CurveVar<C, C::BaseField>>
+where
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+{
+    // constant
+    pub rc: Vec<C::BaseField>, // round_constants
+
+    // public
+    pub h_k_data: Option<C::BaseField>,
+    pub pk_peer_own: Option<C::Affine>,
+    pub h_ct: Option<C::BaseField>,
+    pub num_chunks: Option<C::BaseField>,
+    pub chunk_index: Option<C::BaseField>,
+
+    // witness
+    pub sk_peer_own: Option<C::ScalarField>,
+    pub k_data: Option<C::BaseField>,
+    pub ct_r: Option<C::BaseField>,
+    pub data_chunk: Option<Vec<C::BaseField>>,
+    pub ct_chunk: Option<Vec<C::BaseField>>,
+    pub chunk_path: Option<Path<CiphertextTreeConfig<C::BaseField>>>,
+
+    pub _curve_var: PhantomData<GG>,
+}
+
+#[allow(non_snake_case)]
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for RegisterChunk<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField> + ToConstraintFieldGadget<C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let rc = hashes::mimc7::Parameters {
+            round_constants: self.rc,
+        };
+        let rc = hashes::mimc7::constraints::ParametersVar::new_constant(
+            ark_relations::ns!(cs, "round constants"),
+            &rc,
+        )?;
+
+        let h_k_data = FpVar::new_input(ark_relations::ns!(cs, "h_k_data"), || {
+            self.h_k_data.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let pk_peer_own = GG::new_input(ark_relations::ns!(cs, "pk_peer_own"), || {
+            self.pk_peer_own.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let h_ct = FpVar::new_input(ark_relations::ns!(cs, "h_ct"), || {
+            self.h_ct.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let num_chunks = FpVar::new_input(ark_relations::ns!(cs, "num_chunks"), || {
+            self.num_chunks.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let chunk_index = FpVar::new_input(ark_relations::ns!(cs, "chunk_index"), || {
+            self.chunk_index.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        let sk_peer_own = SecretKeyVar::new_witness(ark_relations::ns!(cs, "sk_peer_own"), || {
+            self.sk_peer_own
+                .map(elgamal::SecretKey::<C>)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let k_data = FpVar::new_witness(ark_relations::ns!(cs, "k_data"), || {
+            self.k_data.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let ct_r = symmetric::constraints::RandomnessVar::new_witness(
+            ark_relations::ns!(cs, "r"),
+            || {
+                self.ct_r
+                    .map(|r| symmetric::Randomness { r })
+                    .ok_or(SynthesisError::AssignmentMissing)
+            },
+        )?;
+        let data_chunk = self
+            .data_chunk
+            .ok_or(SynthesisError::AssignmentMissing)?
+            .iter()
+            .map(|m| FpVar::new_witness(ark_relations::ns!(cs, "data"), || Ok(m)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ct_chunk = self
+            .ct_chunk
+            .ok_or(SynthesisError::AssignmentMissing)?
+            .iter()
+            .map(|c| FpVar::new_witness(ark_relations::ns!(cs, "ct_data"), || Ok(c)))
+            .collect::<Result<Vec<_>, _>>()?;
+        // every ciphertext block of the chunk must encrypt a plaintext block
+        if data_chunk.len() != ct_chunk.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let mut chunk_path = PathVar::<
+            CiphertextTreeConfig<C::BaseField>,
+            C::BaseField,
+            CiphertextTreeConfigVar<C::BaseField>,
+        >::new_witness(ark_relations::ns!(cs, "chunk_path"), || {
+            self.chunk_path
+                .as_ref()
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        //==============================================================================================================
+        // h_k_data == MiMC(pk_peer_own.x || pk_peer_own.y || k_data)
+
+        let mut hash_input = pk_peer_own.to_constraint_field()?;
+        hash_input.push(k_data.clone());
+        MiMCGadget::<C::BaseField>::evaluate(&rc, &hash_input)?.enforce_equal(&h_k_data)?;
+
+        //==============================================================================================================
+        // pk_peer_own == sk_peer_own * G
+
+        let generator = GG::new_constant(
+            ark_relations::ns!(cs, "generator"),
+            elgamal::Parameters::<C>::standard().generator,
+        )?;
+        scalar_mul_le(&generator, &sk_peer_own.0)?.enforce_equal(&pk_peer_own)?;
+
+        //==============================================================================================================
+        // ct_chunk is the leaf at chunk_index < num_chunks of the ciphertext tree with root
+        // tree_root, and h_ct == MiMC(tree_root || num_chunks || ct_r)
+
+        enforce_less_than(&chunk_index, &num_chunks, CHUNK_INDEX_BITS)?;
+
+        chunk_path.set_leaf_index(&chunk_index)?;
+        let tree_root = chunk_path.calculate_root(&rc, &rc, &ct_chunk)?;
+        MiMCGadget::<C::BaseField>::evaluate(&rc, &[tree_root, num_chunks, ct_r.r.clone()])?
+            .enforce_equal(&h_ct)?;
+
+        //==============================================================================================================
+        // ct_chunk = SE.Enc(data_chunk, k_data, chunk_index * chunk_size + j)
+
+        let k_data = symmetric::constraints::SymmetricKeyVar { k: k_data };
+        let first_block = &chunk_index * C::BaseField::from(data_chunk.len() as u64);
+        for (j, (m, c)) in data_chunk.into_iter().zip(ct_chunk.iter()).enumerate() {
+            let index = &first_block + C::BaseField::from(j as u64);
+            let result_ct = SymmetricEncryptionSchemeGadget::<C::BaseField>::encrypt_block(
+                &rc,
+                &ct_r,
+                &k_data,
+                &index,
+                &symmetric::constraints::PlaintextVar { m },
+            )?;
+            result_ct.c.enforce_equal(c)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::{chunk_blocks, ciphertext_hash, ciphertext_tree, RegisterChunk};
+    use crate::datatrade::circuit::{generate_test_input, Registerdata};
+    use crate::gadget::hashes::mimc7;
+    use crate::gadget::symmetric_encrytions::symmetric::{self, SymmetricEncryptionScheme};
+
+    type C = ark_ed_on_bn254::EdwardsProjective;
+    type GG = ark_ed_on_bn254::constraints::EdwardsVar;
+    type F = ark_bn254::Fr;
+
+    const CHUNK_SIZE: usize = 4;
+
+    fn generate_chunk_inputs(register: &Registerdata<C, GG>) -> Vec<RegisterChunk<C, GG>> {
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let ct_data = register.ct_data.clone().unwrap();
+        let tree = ciphertext_tree(&rc, &ct_data, CHUNK_SIZE).unwrap();
+        let num_chunks = ct_data.len() / CHUNK_SIZE;
+        let h_ct = ciphertext_hash(&rc, tree.root(), num_chunks, register.ct_r.unwrap()).unwrap();
+
+        chunk_blocks(register.data.as_ref().unwrap(), CHUNK_SIZE)
+            .into_iter()
+            .zip(chunk_blocks(&ct_data, CHUNK_SIZE))
+            .enumerate()
+            .map(|(i, (data_chunk, ct_chunk))| RegisterChunk {
+                rc: rc.round_constants.clone(),
+                h_k_data: register.h_k_data,
+                pk_peer_own: register.pk_peer_own,
+                h_ct: Some(h_ct),
+                num_chunks: Some(F::from(num_chunks as u64)),
+                chunk_index: Some(F::from(i as u64)),
+                sk_peer_own: register.sk_peer_own,
+                k_data: register.k_data,
+                ct_r: register.ct_r,
+                data_chunk: Some(data_chunk),
+                ct_chunk: Some(ct_chunk),
+                chunk_path: Some(tree.generate_proof(i).unwrap()),
+                _curve_var: std::marker::PhantomData,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_chunk_blocks() {
+        let data: Vec<F> = (1..=5u64).map(F::from).collect();
+        let chunks = chunk_blocks(&data, 2);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks[2], vec![F::from(5u64), F::from(0u64)]);
+
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        assert!(ciphertext_tree(&rc, &data, 2).is_err());
+        assert!(ciphertext_tree(&rc, &data, 5).is_ok());
+    }
+
+    #[test]
+    fn test_register_chunks() {
+        let register = generate_test_input(3 * CHUNK_SIZE).unwrap();
+        let inputs = generate_chunk_inputs(&register);
+        assert_eq!(inputs.len(), 3);
+
+        // every chunk is proven against the same verifying key
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = Groth16::<Bn254>::setup(inputs[0].clone(), &mut rng).unwrap();
+
+        let pk_peer_own = register.pk_peer_own.unwrap();
+        for input in inputs {
+            let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+            let image = vec![
+                input.h_k_data.unwrap(),
+                pk_peer_own.x,
+                pk_peer_own.y,
+                input.h_ct.unwrap(),
+                input.num_chunks.unwrap(),
+                input.chunk_index.unwrap(),
+            ];
+            assert!(Groth16::<Bn254>::verify(&vk, &image, &proof).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_register_chunk_at_other_index() {
+        let register = generate_test_input(3 * CHUNK_SIZE).unwrap();
+        let mut input = generate_chunk_inputs(&register).remove(1);
+        input.chunk_index = Some(F::from(2u64));
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_register_chunk_beyond_num_chunks() {
+        let register = generate_test_input(3 * CHUNK_SIZE).unwrap();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let tree = ciphertext_tree(&rc, register.ct_data.as_ref().unwrap(), CHUNK_SIZE).unwrap();
+
+        // a file registered as two chunks cannot prove its third chunk
+        let mut input = generate_chunk_inputs(&register).remove(2);
+        input.num_chunks = Some(F::from(2u64));
+        input.h_ct = Some(ciphertext_hash(&rc, tree.root(), 2, register.ct_r.unwrap()).unwrap());
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_truncated_file() {
+        let register = generate_test_input(3 * CHUNK_SIZE).unwrap();
+        let inputs = generate_chunk_inputs(&register);
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+
+        // the first two chunks on their own commit to another h_ct
+        let ct_data = register.ct_data.unwrap();
+        let truncated = ciphertext_tree(&rc, &ct_data[..2 * CHUNK_SIZE], CHUNK_SIZE).unwrap();
+        let truncated_h_ct =
+            ciphertext_hash(&rc, truncated.root(), 2, register.ct_r.unwrap()).unwrap();
+        assert_ne!(truncated_h_ct, inputs[0].h_ct.unwrap());
+
+        // and a chunk of the truncated file does not verify against the registered h_ct
+        let mut input = inputs[1].clone();
+        input.num_chunks = Some(F::from(2u64));
+        input.chunk_path = Some(truncated.generate_proof(1).unwrap());
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_register_chunk_with_other_randomness() {
+        let rng = &mut test_rng();
+        let register = generate_test_input(3 * CHUNK_SIZE).unwrap();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let mut input = generate_chunk_inputs(&register).remove(1);
+
+        // the chunk encrypted under another r is consistent on its own
+        let other_r = F::rand(rng);
+        let ct_chunk = input
+            .data_chunk
+            .clone()
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .map(|(j, m)| {
+                SymmetricEncryptionScheme::encrypt_block(
+                    rc.clone(),
+                    symmetric::Randomness { r: other_r },
+                    symmetric::SymmetricKey {
+                        k: register.k_data.unwrap(),
+                    },
+                    (CHUNK_SIZE + j) as u64,
+                    symmetric::Plaintext { m },
+                )
+                .unwrap()
+                .c
+            })
+            .collect::<Vec<_>>();
+
+        // but the registered h_ct binds the r of the other chunks
+        let mut ct_data = register.ct_data.clone().unwrap();
+        ct_data[CHUNK_SIZE..2 * CHUNK_SIZE].copy_from_slice(&ct_chunk);
+        let tree = ciphertext_tree(&rc, &ct_data, CHUNK_SIZE).unwrap();
+        input.ct_r = Some(other_r);
+        input.ct_chunk = Some(ct_chunk);
+        input.chunk_path = Some(tree.generate_proof(1).unwrap());
+        input.h_ct = Some(ciphertext_hash(&rc, tree.root(), 3, register.ct_r.unwrap()).unwrap());
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_register_chunk_with_extra_ciphertext_block() {
+        let rng = &mut test_rng();
+        let register = generate_test_input(3 * CHUNK_SIZE).unwrap();
+        let mut input = generate_chunk_inputs(&register).remove(0);
+        input.ct_chunk.as_mut().unwrap().push(F::rand(rng));
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        assert!(matches!(
+            input.generate_constraints(cs),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+}
@@ -4,12 +4,18 @@ use crate::gadget::{
         constraints::CRHSchemeGadget,
         mimc7::{self, constraints::MiMCGadget},
     },
+    public_encryptions::{
+        elgamal::{
+            self,
+            constraints::{scalar_mul_le, SecretKeyVar},
+        },
+        AsymmetricEncryptionScheme,
+    },
     symmetric_encrytions::{
         constraints::SymmetricEncryptionGadget,
         symmetric::{
             self, constraints::SymmetricEncryptionSchemeGadget, SymmetricEncryptionScheme,
         },
-        SymmetricEncryption,
     },
 };
 use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
@@ -32,8 +38,9 @@ use ark_crypto_primitives::Error;
 
 use ark_crypto_primitives::sponge::Absorb;
 use ark_ec::{bn::Bn, CurveGroup};
-use ark_ff::{Field, PrimeField};
+use ark_ff::{Field, PrimeField, ToConstraintField};
 use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::ToConstraintFieldGadget;
 use ark_r1cs_std::{fields::fp::FpVar, prelude::AllocVar};
 use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
 use ark_std::marker::PhantomData;
@@ -51,9 +58,10 @@ where
     // public
     pub h_ct: Option<C::BaseField>,
     pub h_k_data: Option<C::BaseField>,
-    pub pk_peer_own: Option<C::BaseField>,
+    pub pk_peer_own: Option<C::Affine>,
 
     // witness
+    pub sk_peer_own: Option<C::ScalarField>,
     pub data: Option<Vec<C::BaseField>>,
     pub k_data: Option<C::BaseField>,
     pub ct_r: Option<C::BaseField>,
@@ -62,18 +70,33 @@ where
     pub _curve_var: PhantomData<GG>,
 }
 
+/// Variables of [`Registerdata`] that other circuits build on: the public inputs, in the order
+/// they are allocated, followed by the plaintext blocks. `pk_peer_own` takes one input per
+/// coordinate.
+#[derive(Clone)]
+pub struct RegisterdataVar<F: PrimeField, GG> {
+    pub h_k_data: FpVar<F>,
+    pub pk_peer_own: GG,
+    pub h_ct: FpVar<F>,
+
+    // witness
+    pub data: Vec<FpVar<F>>,
+}
+
 #[allow(non_snake_case)]
-impl<C, GG> ConstraintSynthesizer<C::BaseField> for Registerdata<C, GG>
+impl<C, GG> Registerdata<C, GG>
 where
     C: CurveGroup,
-    GG: CurveVar<C, C::BaseField>,
+    GG: CurveVar<C, C::BaseField> + ToConstraintFieldGadget<C::BaseField>,
     <C as CurveGroup>::BaseField: PrimeField + Absorb,
     for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
 {
-    fn generate_constraints(
+    /// Generate the registration constraints and return the public input variables, so that other
+    /// circuits can extend the registration statement.
+    pub fn synthesize(
         self,
         cs: ark_relations::r1cs::ConstraintSystemRef<C::BaseField>,
-    ) -> ark_relations::r1cs::Result<()> {
+    ) -> ark_relations::r1cs::Result<RegisterdataVar<C::BaseField, GG>> {
         //==============================================================================================================
 
         let rc = hashes::mimc7::Parameters {
@@ -85,27 +108,42 @@ where
         )
         .unwrap();
         //==============================================================================================================
-        //h_k_data == Hash(pk_peer_own || k_data)
+        //h_k_data == Hash(pk_peer_own.x || pk_peer_own.y || k_data)
 
         let h_k_data = FpVar::new_input(ark_relations::ns!(cs, "h_k_data"), || {
             Ok(self.h_k_data.unwrap())
         })
         .unwrap();
 
-        let pk_peer_own = FpVar::new_input(ark_relations::ns!(cs, "pk_peer_own"), || {
-            Ok(self.pk_peer_own.unwrap())
-        })
-        .unwrap();
+        let pk_peer_own = GG::new_input(ark_relations::ns!(cs, "pk_peer_own"), || {
+            self.pk_peer_own.ok_or(SynthesisError::AssignmentMissing)
+        })?;
 
         let binding = self.k_data.unwrap();
         let k_data_binding =
             FpVar::new_witness(ark_relations::ns!(cs, "k_data"), || Ok(binding)).unwrap();
 
-        let hash_input = [pk_peer_own, k_data_binding].to_vec();
+        let mut hash_input = pk_peer_own.to_constraint_field()?;
+        hash_input.push(k_data_binding.clone());
         let result_h_k_data = MiMCGadget::<C::BaseField>::evaluate(&rc, &hash_input).unwrap();
 
         result_h_k_data.enforce_equal(&h_k_data).unwrap();
 
+        //==============================================================================================================
+        // pk_peer_own == sk_peer_own * G
+
+        let sk_peer_own = SecretKeyVar::new_witness(ark_relations::ns!(cs, "sk_peer_own"), || {
+            self.sk_peer_own
+                .map(elgamal::SecretKey::<C>)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let generator = GG::new_constant(
+            ark_relations::ns!(cs, "generator"),
+            elgamal::Parameters::<C>::standard().generator,
+        )?;
+
+        scalar_mul_le(&generator, &sk_peer_own.0)?.enforce_equal(&pk_peer_own)?;
+
         //==============================================================================================================
 
         // h_ct == Hash(CT_data)
@@ -119,8 +157,7 @@ where
             .map(|i| FpVar::new_witness(ark_relations::ns!(cs, "ct_data{i}"), || Ok(i)).unwrap())
             .collect();
 
-        let hash_input = ct_data_binding;
-        let result_h_ct = MiMCGadget::<C::BaseField>::evaluate(&rc, &hash_input).unwrap();
+        let result_h_ct = MiMCGadget::<C::BaseField>::evaluate(&rc, &ct_data_binding).unwrap();
 
         result_h_ct.enforce_equal(&h_ct).unwrap();
 
@@ -136,24 +173,12 @@ where
             })
             .unwrap();
 
-        let binding = self.ct_data.clone().unwrap();
-
-        let ct_data: Vec<symmetric::constraints::CiphertextVar<C::BaseField>> = binding
-            .iter()
-            .map(|i| {
-                <SymmetricEncryptionSchemeGadget<C::BaseField> as SymmetricEncryptionGadget<
-                    SymmetricEncryptionScheme<C::BaseField>,
-                    C::BaseField,
-                >>::CiphertextVar::new_witness(
-                    ark_relations::ns!(cs, "ct_data{i}"),
-                    || {
-                        Ok(symmetric::Ciphertext {
-                            r: self.ct_r.unwrap(),
-                            c: *i,
-                        })
-                    }, // tk_addr_ena_old
-                )
-                .unwrap()
+        // the hashed ciphertext blocks are the ones checked against SE.Enc
+        let ct_data: Vec<symmetric::constraints::CiphertextVar<C::BaseField>> = ct_data_binding
+            .into_iter()
+            .map(|c| symmetric::constraints::CiphertextVar {
+                r: ct_r.r.clone(),
+                c,
             })
             .collect();
 
@@ -174,37 +199,54 @@ where
             })
             .collect();
 
-        let binding = self.k_data.clone().unwrap();
+        // the key hashed into h_k_data is the one encrypting the data
+        let k_data = symmetric::constraints::SymmetricKeyVar { k: k_data_binding };
 
-        let k_data = <SymmetricEncryptionSchemeGadget<C::BaseField> as SymmetricEncryptionGadget<
-            SymmetricEncryptionScheme<C::BaseField>,
-            C::BaseField,
-        >>::SymmetricKeyVar::new_witness(
-            ark_relations::ns!(cs, "k_data"),
-            || Ok(symmetric::SymmetricKey { k: binding }),
-        )
-        .unwrap();
+        // every hashed ciphertext block must encrypt a plaintext block
+        if data.len() != ct_data.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
 
-        for i in 0..self.data.unwrap().len() {
-            let result_ct_data = SymmetricEncryptionSchemeGadget::<C::BaseField>::encrypt(
-                rc.clone(),
-                ct_r.clone(),
-                k_data.clone(),
-                data[i].clone(),
-            )
-            .unwrap();
+        // each block is encrypted under its own pad MiMC(k_data || ct_r || i)
+        for i in 0..data.len() {
+            let index = FpVar::Constant(C::BaseField::from(i as u64));
+            let result_ct_data = SymmetricEncryptionSchemeGadget::<C::BaseField>::encrypt_block(
+                &rc, &ct_r, &k_data, &index, &data[i],
+            )?;
 
-            result_ct_data.enforce_equal(&ct_data[i]);
+            result_ct_data.enforce_equal(&ct_data[i])?;
         }
 
         println!("tiger = {:?}", cs.num_constraints());
 
-        Ok(())
+        Ok(RegisterdataVar {
+            h_k_data,
+            pk_peer_own,
+            h_ct,
+            data: data.into_iter().map(|d| d.m).collect(),
+        })
 
         //==============================================================================================================
     }
 }
 
+#[allow(non_snake_case)]
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for Registerdata<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField> + ToConstraintFieldGadget<C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        self.synthesize(cs)?;
+        Ok(())
+    }
+}
+
 use crate::gadget::hashes::CRHScheme;
 type C = ark_ed_on_bn254::EdwardsProjective;
 type GG = ark_ed_on_bn254::constraints::EdwardsVar;
@@ -222,10 +264,14 @@ pub fn generate_test_input(n: usize) -> Result<Registerdata<C, GG>, Error> {
     };
     //==============================================================================================================
 
-    let pk_peer_own = F::rand(rng);
+    let (pk_peer_own, sk_peer_own) =
+        elgamal::ElGamal::<C>::keygen(&elgamal::Parameters::standard(), rng)?;
     let k_data = F::rand(rng);
-    let h_k_data =
-        H::evaluate(&rc.clone(), [pk_peer_own.clone(), k_data.clone()].to_vec()).unwrap();
+    let mut hash_input = pk_peer_own
+        .to_field_elements()
+        .ok_or("cannot convert pk_peer_own to field elements")?;
+    hash_input.push(k_data);
+    let h_k_data = H::evaluate(&rc.clone(), hash_input).unwrap();
     //==============================================================================================================
 
     let mut data: Vec<F> = Vec::new();
@@ -239,10 +285,11 @@ pub fn generate_test_input(n: usize) -> Result<Registerdata<C, GG>, Error> {
 
     for i in 0..n {
         ct_data.push(
-            SEEnc::encrypt(
+            SEEnc::encrypt_block(
                 rc.clone(),
                 random.clone(),
                 key.clone(),
+                i as u64,
                 symmetric::Plaintext { m: data[i] },
             )
             .unwrap()
@@ -273,6 +320,7 @@ pub fn generate_test_input(n: usize) -> Result<Registerdata<C, GG>, Error> {
         h_ct: Some(h_ct),
         h_k_data: Some(h_k_data),
         pk_peer_own: Some(pk_peer_own),
+        sk_peer_own: Some(sk_peer_own.0),
         data: Some(data),
         k_data: Some(k_data),
         ct_r: Some(cin_r),
@@ -411,6 +459,26 @@ pub fn test_data() -> String {
 
 use std::str::FromStr;
 
+#[test]
+fn test_registerdata_binds_secret_key() {
+    use ark_relations::r1cs::ConstraintSystem;
+
+    let rng = &mut test_rng();
+    let input = generate_test_input(4).unwrap();
+
+    let cs = ConstraintSystem::<F>::new_ref();
+    input.clone().generate_constraints(cs.clone()).unwrap();
+    assert!(cs.is_satisfied().unwrap());
+
+    // registering someone else's key without its secret key
+    let mut input = input;
+    input.sk_peer_own = Some(UniformRand::rand(rng));
+
+    let cs = ConstraintSystem::<F>::new_ref();
+    input.generate_constraints(cs.clone()).unwrap();
+    assert!(!cs.is_satisfied().unwrap());
+}
+
 #[test]
 fn test_string_to_fr() {
     let rng = &mut test_rng();
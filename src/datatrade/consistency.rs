@@ -0,0 +1,178 @@
+use crate::datatrade::circuit::{Registerdata, RegisterdataVar};
+use crate::gadget::hashes::{self, constraints::CRHSchemeGadget, mimc7::constraints::MiMCGadget};
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::ToConstraintFieldGadget;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::AllocVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+
+/// [`Registerdata`] which additionally proves that the plaintext hashes to `h_data = MiMC(data)`,
+/// so that a buyer who decrypts `ct_data` with the registered `k_data` knows the result matches
+/// the advertised `h_data`.
+///
+/// The public inputs are the ones of [`Registerdata`] followed by `h_data`.
+#[allow(non_snake_case)]
+#[derive(Clone)]
+pub struct DataConsistency<C: CurveGroup, GG: CurveVar<C, C::BaseField>>
+where
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+{
+    pub register: Registerdata<C, GG>,
+
+    // public
+    pub h_data: Option<C::BaseField>,
+}
+
+#[allow(non_snake_case)]
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for DataConsistency<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField> + ToConstraintFieldGadget<C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let rc = hashes::mimc7::Parameters {
+            round_constants: self.register.rc.clone(),
+        };
+        let rc = hashes::mimc7::constraints::ParametersVar::new_constant(
+            ark_relations::ns!(cs, "round constants"),
+            &rc,
+        )?;
+
+        // ct_data == SE.Enc(data, k_data) and h_ct == MiMC(ct_data)
+        let RegisterdataVar { data, .. } = self.register.synthesize(cs.clone())?;
+
+        //==============================================================================================================
+        // h_data == MiMC(data)
+
+        let h_data = FpVar::new_input(ark_relations::ns!(cs, "h_data"), || {
+            self.h_data.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        MiMCGadget::<C::BaseField>::evaluate(&rc, &data)?.enforce_equal(&h_data)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::DataConsistency;
+    use crate::datatrade::circuit::generate_test_input;
+    use crate::gadget::hashes::{mimc7, CRHScheme};
+    use crate::gadget::symmetric_encrytions::symmetric::{self, SymmetricEncryptionScheme};
+
+    type C = ark_ed_on_bn254::EdwardsProjective;
+    type GG = ark_ed_on_bn254::constraints::EdwardsVar;
+    type F = ark_bn254::Fr;
+    type SEEnc = SymmetricEncryptionScheme<F>;
+
+    fn generate_consistency_input() -> DataConsistency<C, GG> {
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let register = generate_test_input(4).unwrap();
+        let h_data = mimc7::MiMC::<F>::evaluate(&rc, register.data.clone().unwrap()).unwrap();
+
+        DataConsistency {
+            register,
+            h_data: Some(h_data),
+        }
+    }
+
+    #[test]
+    fn test_data_consistency() {
+        let input = generate_consistency_input();
+
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = Groth16::<Bn254>::setup(input.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+
+        let pk_peer_own = input.register.pk_peer_own.unwrap();
+        let image = vec![
+            input.register.h_k_data.unwrap(),
+            pk_peer_own.x,
+            pk_peer_own.y,
+            input.register.h_ct.unwrap(),
+            input.h_data.unwrap(),
+        ];
+        assert!(Groth16::<Bn254>::verify(&vk, &image, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_data_consistency_with_other_plaintext() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+
+        // advertising the hash of data other than the encrypted one
+        let mut input = generate_consistency_input();
+        let mut other_data = input.register.data.clone().unwrap();
+        other_data[0] = F::rand(rng);
+        input.h_data = Some(mimc7::MiMC::<F>::evaluate(&rc, other_data).unwrap());
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_data_consistency_with_other_ciphertext() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let honest = generate_consistency_input();
+        let register = &honest.register;
+
+        // the same plaintext encrypted under another key, with every public input kept honest
+        let other_key = F::rand(rng);
+        let other_ct_data: Vec<F> = register
+            .data
+            .clone()
+            .unwrap()
+            .into_iter()
+            .enumerate()
+            .map(|(i, m)| {
+                SEEnc::encrypt_block(
+                    rc.clone(),
+                    symmetric::Randomness {
+                        r: register.ct_r.unwrap(),
+                    },
+                    symmetric::SymmetricKey { k: other_key },
+                    i as u64,
+                    symmetric::Plaintext { m },
+                )
+                .unwrap()
+                .c
+            })
+            .collect();
+
+        for k_data in [register.k_data.unwrap(), other_key] {
+            let mut input = honest.clone();
+            input.register.k_data = Some(k_data);
+            input.register.ct_data = Some(other_ct_data.clone());
+
+            let cs = ConstraintSystem::<F>::new_ref();
+            input.generate_constraints(cs.clone()).unwrap();
+            assert!(!cs.is_satisfied().unwrap());
+        }
+    }
+}
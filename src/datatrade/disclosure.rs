@@ -0,0 +1,245 @@
+use crate::gadget::{
+    hashes::{self, constraints::CRHSchemeGadget, mimc7::constraints::MiMCGadget},
+    symmetric_encrytions::symmetric::{self, constraints::SymmetricEncryptionSchemeGadget},
+};
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::ToConstraintFieldGadget;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::AllocVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_std::marker::PhantomData;
+
+/// Previews registered data: proves that the plaintext blocks `revealed` at positions `indices`
+/// decrypt from the ciphertext hashing to `h_ct` under the key committed to in `h_k_data`, while
+/// the other blocks stay hidden.
+///
+/// Each block is encrypted under its own pad `MiMC(k_data || ct_r || index)`, so a revealed block
+/// gives away only the pad of that block.
+///
+/// The public inputs are, in order, `h_k_data`, `pk_peer_own`, `h_ct`, and then each index
+/// followed by its revealed block.
+#[allow(non_snake_case)]
+#[derive(Clone)]
+pub struct SelectiveDisclosure<C: CurveGroup, GG: CurveVar<C, C::BaseField>>
+where
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+{
+    // constant
+    pub rc: Vec<C::BaseField>, // round_constants
+
+    // public
+    pub h_k_data: Option<C::BaseField>,
+    pub pk_peer_own: Option<C::Affine>,
+    pub h_ct: Option<C::BaseField>,
+    pub indices: Option<Vec<C::BaseField>>,
+    pub revealed: Option<Vec<C::BaseField>>,
+
+    // witness
+    pub k_data: Option<C::BaseField>,
+    pub ct_r: Option<C::BaseField>,
+    pub ct_data: Option<Vec<C::BaseField>>,
+
+    pub _curve_var: PhantomData<GG>,
+}
+
+#[allow(non_snake_case)]
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for SelectiveDisclosure<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField> + ToConstraintFieldGadget<C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let rc = hashes::mimc7::Parameters {
+            round_constants: self.rc,
+        };
+        let rc = hashes::mimc7::constraints::ParametersVar::new_constant(
+            ark_relations::ns!(cs, "round constants"),
+            &rc,
+        )?;
+
+        let h_k_data = FpVar::new_input(ark_relations::ns!(cs, "h_k_data"), || {
+            self.h_k_data.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let pk_peer_own = GG::new_input(ark_relations::ns!(cs, "pk_peer_own"), || {
+            self.pk_peer_own.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let h_ct = FpVar::new_input(ark_relations::ns!(cs, "h_ct"), || {
+            self.h_ct.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        let indices = self.indices.ok_or(SynthesisError::AssignmentMissing)?;
+        let revealed = self.revealed.ok_or(SynthesisError::AssignmentMissing)?;
+        // every index must come with its revealed block
+        if indices.len() != revealed.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let revealed = indices
+            .iter()
+            .zip(revealed.iter())
+            .map(|(i, m)| {
+                let index = FpVar::new_input(ark_relations::ns!(cs, "index"), || Ok(i))?;
+                let m = FpVar::new_input(ark_relations::ns!(cs, "revealed"), || Ok(m))?;
+                Ok((index, m))
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        let k_data = FpVar::new_witness(ark_relations::ns!(cs, "k_data"), || {
+            self.k_data.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let ct_r = symmetric::constraints::RandomnessVar::new_witness(
+            ark_relations::ns!(cs, "r"),
+            || {
+                self.ct_r
+                    .map(|r| symmetric::Randomness { r })
+                    .ok_or(SynthesisError::AssignmentMissing)
+            },
+        )?;
+        let ct_data = self
+            .ct_data
+            .ok_or(SynthesisError::AssignmentMissing)?
+            .iter()
+            .map(|c| FpVar::new_witness(ark_relations::ns!(cs, "ct_data"), || Ok(c)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        //==============================================================================================================
+        // h_k_data == MiMC(pk_peer_own.x || pk_peer_own.y || k_data)
+
+        let mut hash_input = pk_peer_own.to_constraint_field()?;
+        hash_input.push(k_data.clone());
+        MiMCGadget::<C::BaseField>::evaluate(&rc, &hash_input)?.enforce_equal(&h_k_data)?;
+
+        //==============================================================================================================
+        // h_ct == MiMC(ct_data)
+
+        MiMCGadget::<C::BaseField>::evaluate(&rc, &ct_data)?.enforce_equal(&h_ct)?;
+
+        //==============================================================================================================
+        // ct_data[index] == SE.Enc(revealed, k_data, index) for each revealed block
+
+        let k_data = symmetric::constraints::SymmetricKeyVar { k: k_data };
+        for (index, m) in revealed {
+            // select ct_data[index], enforcing that exactly one position matches the index
+            let mut selected = FpVar::zero();
+            let mut matches = FpVar::zero();
+            for (i, c) in ct_data.iter().enumerate() {
+                let is_index =
+                    FpVar::from(index.is_eq(&FpVar::Constant(C::BaseField::from(i as u64)))?);
+                selected += &is_index * c;
+                matches += is_index;
+            }
+            matches.enforce_equal(&FpVar::one())?;
+
+            let result_ct = SymmetricEncryptionSchemeGadget::<C::BaseField>::encrypt_block(
+                &rc,
+                &ct_r,
+                &k_data,
+                &index,
+                &symmetric::constraints::PlaintextVar { m },
+            )?;
+            result_ct.c.enforce_equal(&selected)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisError};
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::SelectiveDisclosure;
+    use crate::datatrade::circuit::generate_test_input;
+    use crate::gadget::hashes::mimc7;
+
+    type C = ark_ed_on_bn254::EdwardsProjective;
+    type GG = ark_ed_on_bn254::constraints::EdwardsVar;
+    type F = ark_bn254::Fr;
+
+    fn generate_disclosure_input(indices: &[usize]) -> SelectiveDisclosure<C, GG> {
+        let register = generate_test_input(6).unwrap();
+        let data = register.data.unwrap();
+
+        SelectiveDisclosure {
+            rc: mimc7::parameters::get_bn256_round_constants(),
+            h_k_data: register.h_k_data,
+            pk_peer_own: register.pk_peer_own,
+            h_ct: register.h_ct,
+            indices: Some(indices.iter().map(|i| F::from(*i as u64)).collect()),
+            revealed: Some(indices.iter().map(|i| data[*i]).collect()),
+            k_data: register.k_data,
+            ct_r: register.ct_r,
+            ct_data: register.ct_data,
+            _curve_var: std::marker::PhantomData,
+        }
+    }
+
+    #[test]
+    fn test_selective_disclosure() {
+        let input = generate_disclosure_input(&[1, 4]);
+
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = Groth16::<Bn254>::setup(input.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+
+        let pk_peer_own = input.pk_peer_own.unwrap();
+        let mut image = vec![
+            input.h_k_data.unwrap(),
+            pk_peer_own.x,
+            pk_peer_own.y,
+            input.h_ct.unwrap(),
+        ];
+        for (i, m) in input.indices.unwrap().iter().zip(input.revealed.unwrap()) {
+            image.push(*i);
+            image.push(m);
+        }
+        assert!(Groth16::<Bn254>::verify(&vk, &image, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_selective_disclosure_with_other_block() {
+        let rng = &mut test_rng();
+        let mut input = generate_disclosure_input(&[1, 4]);
+        input.revealed.as_mut().unwrap()[1] = F::rand(rng);
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_selective_disclosure_with_index_out_of_range() {
+        let mut input = generate_disclosure_input(&[1]);
+        input.indices = Some(vec![F::from(6u64)]);
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_selective_disclosure_with_missing_block() {
+        let mut input = generate_disclosure_input(&[1, 4]);
+        input.revealed.as_mut().unwrap().pop();
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        assert!(matches!(
+            input.generate_constraints(cs),
+            Err(SynthesisError::Unsatisfiable)
+        ));
+    }
+}
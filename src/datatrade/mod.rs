@@ -1,2 +1,8 @@
+pub mod chunked;
 pub mod circuit;
+pub mod consistency;
+pub mod disclosure;
+pub mod purchase;
+pub mod registry;
+pub mod settlement;
 pub mod tool;
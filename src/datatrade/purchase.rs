@@ -0,0 +1,220 @@
+use crate::gadget::{
+    hashes::{
+        self,
+        constraints::CRHSchemeGadget,
+        mimc7::{self, constraints::MiMCGadget},
+        CRHScheme,
+    },
+    nullifier::{self, constraints as nullifier_constraints},
+    public_encryptions::elgamal::{
+        self,
+        constraints::{scalar_mul_le, SecretKeyVar},
+    },
+};
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_crypto_primitives::Error;
+use ark_ec::CurveGroup;
+use ark_ff::{PrimeField, ToConstraintField};
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::ToConstraintFieldGadget;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::AllocVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_std::marker::PhantomData;
+
+/// Returns the purchase token `MiMC(pk_buyer || h_ct || nonce)` issued to a buyer for the data
+/// with ciphertext hash `h_ct`.
+pub fn purchase_token<C>(
+    rc: &mimc7::Parameters<C::BaseField>,
+    pk_buyer: &C::Affine,
+    h_ct: C::BaseField,
+    nonce: C::BaseField,
+) -> Result<C::BaseField, Error>
+where
+    C: CurveGroup,
+    C::BaseField: PrimeField + Absorb,
+    C::Affine: ToConstraintField<C::BaseField>,
+{
+    let mut input = pk_buyer
+        .to_field_elements()
+        .ok_or("cannot convert pk_buyer to field elements")?;
+    input.push(h_ct);
+    input.push(nonce);
+    mimc7::MiMC::<C::BaseField>::evaluate(rc, input)
+}
+
+/// Returns the nullifier revealed when spending a token with `nonce`.
+pub fn purchase_nullifier<C>(
+    rc: &mimc7::Parameters<C::BaseField>,
+    sk_buyer: C::ScalarField,
+    nonce: C::BaseField,
+) -> Result<C::BaseField, Error>
+where
+    C: CurveGroup,
+    C::BaseField: PrimeField + Absorb,
+{
+    nullifier::nullifier(rc, nullifier::secret_key_to_field(sk_buyer), nonce)
+}
+
+/// Spends the purchase token `cm_token` for the data with ciphertext hash `h_ct`, revealing the
+/// nullifier `nf = MiMC(sk_buyer || nonce)` so that the token cannot be spent twice.
+///
+/// The public inputs are, in order, `h_ct`, `cm_token` and `nf`.
+#[allow(non_snake_case)]
+#[derive(Clone)]
+pub struct Purchase<C: CurveGroup, GG: CurveVar<C, C::BaseField>>
+where
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+{
+    // constant
+    pub rc: Vec<C::BaseField>, // round_constants
+
+    // public
+    pub h_ct: Option<C::BaseField>,
+    pub cm_token: Option<C::BaseField>,
+    pub nf: Option<C::BaseField>,
+
+    // witness
+    pub sk_buyer: Option<C::ScalarField>,
+    pub nonce: Option<C::BaseField>,
+
+    pub _curve_var: PhantomData<GG>,
+}
+
+#[allow(non_snake_case)]
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for Purchase<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField> + ToConstraintFieldGadget<C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let rc = hashes::mimc7::Parameters {
+            round_constants: self.rc,
+        };
+        let rc = hashes::mimc7::constraints::ParametersVar::new_constant(
+            ark_relations::ns!(cs, "round constants"),
+            &rc,
+        )?;
+
+        let h_ct = FpVar::new_input(ark_relations::ns!(cs, "h_ct"), || {
+            self.h_ct.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let cm_token = FpVar::new_input(ark_relations::ns!(cs, "cm_token"), || {
+            self.cm_token.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let nf = FpVar::new_input(ark_relations::ns!(cs, "nf"), || {
+            self.nf.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        let sk_buyer = SecretKeyVar::new_witness(ark_relations::ns!(cs, "sk_buyer"), || {
+            self.sk_buyer
+                .map(elgamal::SecretKey::<C>)
+                .ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let nonce = FpVar::new_witness(ark_relations::ns!(cs, "nonce"), || {
+            self.nonce.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        //==============================================================================================================
+        // cm_token == MiMC(sk_buyer * G || h_ct || nonce)
+
+        let generator = GG::new_constant(
+            ark_relations::ns!(cs, "generator"),
+            elgamal::Parameters::<C>::standard().generator,
+        )?;
+        let pk_buyer = scalar_mul_le(&generator, &sk_buyer.0)?;
+
+        let mut token_input = pk_buyer.to_constraint_field()?;
+        token_input.push(h_ct);
+        token_input.push(nonce.clone());
+        MiMCGadget::<C::BaseField>::evaluate(&rc, &token_input)?.enforce_equal(&cm_token)?;
+
+        //==============================================================================================================
+        // nf == MiMC(sk_buyer || nonce)
+
+        let sk_buyer = nullifier_constraints::secret_key_to_field(&sk_buyer.0)?;
+        nullifier_constraints::nullifier(&rc, &sk_buyer, &nonce)?.enforce_equal(&nf)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::{purchase_nullifier, purchase_token, Purchase};
+    use crate::gadget::hashes::mimc7;
+    use crate::gadget::nullifier::NullifierSet;
+    use crate::gadget::public_encryptions::elgamal::{ElGamal, Parameters};
+    use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+
+    type C = ark_ed_on_bn254::EdwardsProjective;
+    type GG = ark_ed_on_bn254::constraints::EdwardsVar;
+    type F = ark_bn254::Fr;
+
+    fn generate_purchase_input() -> Purchase<C, GG> {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+
+        let (pk_buyer, sk_buyer) = ElGamal::<C>::keygen(&Parameters::standard(), rng).unwrap();
+        let h_ct = F::rand(rng);
+        let nonce = F::rand(rng);
+
+        Purchase {
+            cm_token: Some(purchase_token::<C>(&rc, &pk_buyer, h_ct, nonce).unwrap()),
+            nf: Some(purchase_nullifier::<C>(&rc, sk_buyer.0, nonce).unwrap()),
+            rc: rc.round_constants,
+            h_ct: Some(h_ct),
+            sk_buyer: Some(sk_buyer.0),
+            nonce: Some(nonce),
+            _curve_var: std::marker::PhantomData,
+        }
+    }
+
+    #[test]
+    fn test_purchase() {
+        let input = generate_purchase_input();
+
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = Groth16::<Bn254>::setup(input.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+
+        let image = vec![
+            input.h_ct.unwrap(),
+            input.cm_token.unwrap(),
+            input.nf.unwrap(),
+        ];
+        assert!(Groth16::<Bn254>::verify(&vk, &image, &proof).unwrap());
+
+        // the verifier accepts the nullifier once
+        let mut spent = NullifierSet::new();
+        spent.spend(input.nf.unwrap()).unwrap();
+        assert!(spent.spend(input.nf.unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_purchase_with_wrong_nullifier() {
+        let rng = &mut test_rng();
+        let mut input = generate_purchase_input();
+        input.nf = Some(F::rand(rng));
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::datatrade::circuit::{Registerdata, RegisterdataVar};
+use crate::gadget::{
+    hashes::{
+        self,
+        constraints::CRHSchemeGadget,
+        mimc7::{self, constraints::MiMCGadget},
+        CRHScheme,
+    },
+    merkle_tree::{
+        constraints::PathVar,
+        mimc::{constraints::MiMCMerkleTreeConfigGadget, MiMCMerkleTreeConfig},
+        Path,
+    },
+};
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_crypto_primitives::Error;
+use ark_ec::CurveGroup;
+use ark_ff::PrimeField;
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::ToConstraintFieldGadget;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::AllocVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+
+pub type RegistryConfig<F> = MiMCMerkleTreeConfig<F>;
+pub type RegistryConfigVar<F> = MiMCMerkleTreeConfigGadget<F>;
+
+/// Returns the registry leaf of a registration, `MiMC(h_ct || h_k_data || pk_peer_own)` where
+/// `pk_peer_own` is given by its coordinates.
+pub fn registry_leaf<F: PrimeField + Absorb>(
+    rc: &mimc7::Parameters<F>,
+    h_ct: F,
+    h_k_data: F,
+    pk_peer_own: &[F],
+) -> Result<F, Error> {
+    let mut input = vec![h_ct, h_k_data];
+    input.extend_from_slice(pk_peer_own);
+    mimc7::MiMC::<F>::evaluate(rc, input)
+}
+
+/// [`Registerdata`] which additionally proves that the registration is a leaf of the registry
+/// Merkle tree with root `registry_root`.
+///
+/// The public inputs are the ones of [`Registerdata`] followed by `registry_root`.
+#[allow(non_snake_case)]
+#[derive(Clone)]
+pub struct RegisterdataInRegistry<C: CurveGroup, GG: CurveVar<C, C::BaseField>>
+where
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+{
+    pub register: Registerdata<C, GG>,
+
+    // public
+    pub registry_root: Option<C::BaseField>,
+
+    // witness
+    pub registry_path: Option<Path<RegistryConfig<C::BaseField>>>,
+}
+
+#[allow(non_snake_case)]
+impl<C, GG> ConstraintSynthesizer<C::BaseField> for RegisterdataInRegistry<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, C::BaseField> + ToConstraintFieldGadget<C::BaseField>,
+    <C as CurveGroup>::BaseField: PrimeField + Absorb,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<C::BaseField>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let rc = hashes::mimc7::Parameters {
+            round_constants: self.register.rc.clone(),
+        };
+        let rc = hashes::mimc7::constraints::ParametersVar::new_constant(
+            ark_relations::ns!(cs, "round constants"),
+            &rc,
+        )?;
+
+        let RegisterdataVar {
+            h_k_data,
+            pk_peer_own,
+            h_ct,
+            ..
+        } = self.register.synthesize(cs.clone())?;
+
+        //==============================================================================================================
+        // MiMC(h_ct || h_k_data || pk_peer_own) is a leaf of the registry
+
+        let registry_root = FpVar::new_input(ark_relations::ns!(cs, "registry_root"), || {
+            self.registry_root.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        let registry_path = PathVar::<
+            RegistryConfig<C::BaseField>,
+            C::BaseField,
+            RegistryConfigVar<C::BaseField>,
+        >::new_witness(
+            ark_relations::ns!(cs, "registry_path"),
+            || {
+                self.registry_path
+                    .as_ref()
+                    .ok_or(SynthesisError::AssignmentMissing)
+            },
+        )?;
+
+        let mut leaf_input = vec![h_ct, h_k_data];
+        leaf_input.extend(pk_peer_own.to_constraint_field()?);
+        let leaf = MiMCGadget::<C::BaseField>::evaluate(&rc, &leaf_input)?;
+
+        registry_path
+            .verify_membership(&rc, &rc, &registry_root, &[leaf])?
+            .enforce_equal(&Boolean::TRUE)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_ff::ToConstraintField;
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::{registry_leaf, RegisterdataInRegistry};
+    use crate::datatrade::circuit::generate_test_input;
+    use crate::gadget::hashes::mimc7;
+    use crate::gadget::merkle_tree::MerkleTree;
+
+    type C = ark_ed_on_bn254::EdwardsProjective;
+    type GG = ark_ed_on_bn254::constraints::EdwardsVar;
+    type F = ark_bn254::Fr;
+
+    fn generate_registry_input(
+        registry_size: usize,
+        index: usize,
+    ) -> RegisterdataInRegistry<C, GG> {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let register = generate_test_input(4).unwrap();
+
+        let mut leaves: Vec<Vec<F>> = (0..registry_size).map(|_| vec![F::rand(rng)]).collect();
+        leaves[index] = vec![registry_leaf(
+            &rc,
+            register.h_ct.unwrap(),
+            register.h_k_data.unwrap(),
+            &register.pk_peer_own.unwrap().to_field_elements().unwrap(),
+        )
+        .unwrap()];
+        let registry = MerkleTree::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+
+        RegisterdataInRegistry {
+            register,
+            registry_root: Some(registry.root()),
+            registry_path: Some(registry.generate_proof(index).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_registerdata_in_registry() {
+        let input = generate_registry_input(6, 3);
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.clone().generate_constraints(cs.clone()).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = Groth16::<Bn254>::setup(input.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+
+        let pk_peer_own = input.register.pk_peer_own.unwrap();
+        let image = vec![
+            input.register.h_k_data.unwrap(),
+            pk_peer_own.x,
+            pk_peer_own.y,
+            input.register.h_ct.unwrap(),
+            input.registry_root.unwrap(),
+        ];
+        assert!(Groth16::<Bn254>::verify(&vk, &image, &proof).unwrap());
+    }
+
+    #[test]
+    fn test_registerdata_not_in_registry() {
+        let rng = &mut test_rng();
+        let mut input = generate_registry_input(6, 3);
+        input.registry_root = Some(F::rand(rng));
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
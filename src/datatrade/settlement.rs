@@ -0,0 +1,409 @@
+use crate::gadget::{
+    commitments::{
+        mimc::{
+            constraints::{MiMCCommitmentGadget, RandomnessVar as OpeningVar},
+            MiMCCommitment, Randomness,
+        },
+        CommitmentGadget, CommitmentScheme,
+    },
+    hashes::{self, constraints::CRHSchemeGadget, mimc7, mimc7::constraints::MiMCGadget},
+    public_encryptions::{
+        constraints::AsymmetricEncryptionGadget,
+        elgamal::{
+            self,
+            constraints::{PublicKeyVar, RandomnessVar},
+        },
+        hashed_elgamal::{
+            self,
+            constraints::{HashedElGamalEncGadget, OutputVar, ParametersVar, PlaintextVar},
+        },
+    },
+    range_check::constraints::enforce_bits,
+};
+
+use ark_bn254::Fr;
+use ark_crypto_primitives::Error;
+use ark_ec::CurveGroup;
+use ark_ff::ToConstraintField;
+use ark_r1cs_std::prelude::*;
+use ark_r1cs_std::ToConstraintFieldGadget;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::AllocVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, SynthesisError};
+use ark_std::marker::PhantomData;
+
+/// Number of bits of a price, matching the settlement contract's integer width.
+pub const PRICE_BITS: usize = 64;
+
+/// Number of elements of the hashed ElGamal ciphertext of `k_data`.
+pub const CT_K_DATA_LEN: usize = 1;
+
+/// Returns the buyer's order commitment to `h_ct || pk_buyer.x || pk_buyer.y || price`, opened by
+/// `opening`, to pay `price` for the data with ciphertext hash `h_ct` delivered to `pk_buyer`.
+pub fn order_commitment<A: ToConstraintField<Fr>>(
+    rc: &mimc7::Parameters<Fr>,
+    h_ct: Fr,
+    pk_buyer: &A,
+    price: Fr,
+    opening: Fr,
+) -> Result<Fr, Error> {
+    let mut input = vec![h_ct];
+    input.extend(
+        pk_buyer
+            .to_field_elements()
+            .ok_or("invalid buyer public key")?,
+    );
+    input.push(price);
+    MiMCCommitment::<Fr>::commit(rc, &input, &Randomness(opening))
+}
+
+/// Settles a trade: the seller proves that the registered `h_k_data` opens to `k_data`, that
+/// `ct_k_data` encrypts `k_data` to the buyer's public key with hashed ElGamal, and that `price`
+/// is the one committed to in the buyer's order `cm_order` for the data `h_ct` and the key
+/// `pk_buyer`. A settlement
+/// contract checks the proof against these public inputs before releasing the payment.
+///
+/// The contract must also check that `h_k_data`, `pk_seller` and `h_ct` were registered together
+/// with a [`Registerdata`](crate::datatrade::circuit::Registerdata) proof, which ties the key
+/// opening `h_k_data` to the one encrypting the ciphertext behind `h_ct`.
+///
+/// The public inputs are, in order, `h_k_data`, `pk_seller`, `h_ct`, `pk_buyer`, `ct_k_data`,
+/// `price` and `cm_order`, with one input per point coordinate. `ct_k_data` always holds
+/// [`CT_K_DATA_LEN`] elements.
+#[allow(non_snake_case)]
+#[derive(Clone)]
+pub struct Settlement<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>> {
+    // constant
+    pub rc: Vec<Fr>, // round_constants
+
+    // public
+    pub h_k_data: Option<Fr>,
+    pub pk_seller: Option<C::Affine>,
+    pub h_ct: Option<Fr>,
+    pub pk_buyer: Option<C::Affine>,
+    pub ct_k_data: Option<hashed_elgamal::Ciphertext<C>>,
+    pub price: Option<Fr>,
+    pub cm_order: Option<Fr>,
+
+    // witness
+    pub k_data: Option<Fr>,
+    pub ct_k_data_r: Option<C::ScalarField>,
+    pub order_opening: Option<Fr>,
+
+    pub _curve_var: PhantomData<GG>,
+}
+
+#[allow(non_snake_case)]
+impl<C, GG> ConstraintSynthesizer<Fr> for Settlement<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    C::Affine: ToConstraintField<Fr>,
+    GG: CurveVar<C, Fr> + ToConstraintFieldGadget<Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn generate_constraints(
+        self,
+        cs: ark_relations::r1cs::ConstraintSystemRef<Fr>,
+    ) -> ark_relations::r1cs::Result<()> {
+        let rc = hashes::mimc7::Parameters {
+            round_constants: self.rc,
+        };
+        let parameters = ParametersVar::<C, GG>::new_constant(
+            ark_relations::ns!(cs, "parameters"),
+            hashed_elgamal::Parameters::<C> {
+                generator: elgamal::Parameters::<C>::standard().generator,
+                hash: rc.clone(),
+            },
+        )?;
+        let rc = hashes::mimc7::constraints::ParametersVar::new_constant(
+            ark_relations::ns!(cs, "round constants"),
+            &rc,
+        )?;
+
+        let h_k_data = FpVar::new_input(ark_relations::ns!(cs, "h_k_data"), || {
+            self.h_k_data.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let pk_seller = GG::new_input(ark_relations::ns!(cs, "pk_seller"), || {
+            self.pk_seller.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let h_ct = FpVar::new_input(ark_relations::ns!(cs, "h_ct"), || {
+            self.h_ct.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let pk_buyer =
+            PublicKeyVar::<C, GG>::new_input(ark_relations::ns!(cs, "pk_buyer"), || {
+                self.pk_buyer.ok_or(SynthesisError::AssignmentMissing)
+            })?;
+        let ct_k_data = OutputVar::<C, GG>::new_variable_with_len(
+            ark_relations::ns!(cs, "ct_k_data"),
+            || self.ct_k_data.ok_or(SynthesisError::AssignmentMissing),
+            CT_K_DATA_LEN,
+            AllocationMode::Input,
+        )?;
+        let price = FpVar::new_input(ark_relations::ns!(cs, "price"), || {
+            self.price.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let cm_order = FpVar::new_input(ark_relations::ns!(cs, "cm_order"), || {
+            self.cm_order.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        let k_data = FpVar::new_witness(ark_relations::ns!(cs, "k_data"), || {
+            self.k_data.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+        let ct_k_data_r =
+            RandomnessVar::new_witness(ark_relations::ns!(cs, "ct_k_data_r"), || {
+                self.ct_k_data_r
+                    .map(elgamal::Randomness::<C>)
+                    .ok_or(SynthesisError::AssignmentMissing)
+            })?;
+        let order_opening = FpVar::new_witness(ark_relations::ns!(cs, "order_opening"), || {
+            self.order_opening.ok_or(SynthesisError::AssignmentMissing)
+        })?;
+
+        //==============================================================================================================
+        // h_k_data == MiMC(pk_seller.x || pk_seller.y || k_data)
+
+        let mut hash_input = pk_seller.to_constraint_field()?;
+        hash_input.push(k_data.clone());
+        MiMCGadget::<Fr>::evaluate(&rc, &hash_input)?.enforce_equal(&h_k_data)?;
+
+        //==============================================================================================================
+        // ct_k_data == HashedElGamal.Enc(pk_buyer, k_data)
+
+        HashedElGamalEncGadget::<C, GG>::encrypt(
+            &parameters,
+            &PlaintextVar(vec![k_data]),
+            &ct_k_data_r,
+            &pk_buyer,
+        )?
+        .enforce_equal(&ct_k_data)?;
+
+        //==============================================================================================================
+        // cm_order commits to h_ct || pk_buyer.x || pk_buyer.y || price with price < 2^PRICE_BITS
+
+        enforce_bits(&price, PRICE_BITS)?;
+        let mut order_input = vec![h_ct];
+        order_input.extend(pk_buyer.pk.to_constraint_field()?);
+        order_input.push(price);
+        MiMCCommitmentGadget::<Fr>::verify(
+            &rc,
+            &order_input,
+            &OpeningVar(order_opening),
+            &cm_order,
+        )?
+        .enforce_equal(&Boolean::TRUE)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_ff::ToConstraintField;
+    use ark_groth16::Groth16;
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::{order_commitment, Settlement, CT_K_DATA_LEN};
+    use crate::datatrade::circuit::{generate_test_input, Registerdata};
+    use crate::gadget::hashes::{mimc7, CRHScheme};
+    use crate::gadget::public_encryptions::elgamal::{self, Randomness};
+    use crate::gadget::public_encryptions::hashed_elgamal::HashedElGamal;
+    use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+    use crate::gadget::symmetric_encrytions::symmetric::{self, SymmetricEncryptionScheme};
+
+    type C = ark_ed_on_bn254::EdwardsProjective;
+    type GG = ark_ed_on_bn254::constraints::EdwardsVar;
+    type F = ark_bn254::Fr;
+
+    fn generate_settlement_input() -> (Settlement<C, GG>, elgamal::SecretKey<C>) {
+        settle(&generate_test_input(4).unwrap())
+    }
+
+    /// Settles the data of `register` with a fresh buyer.
+    fn settle(register: &Registerdata<C, GG>) -> (Settlement<C, GG>, elgamal::SecretKey<C>) {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+
+        let pp = HashedElGamal::<C>::setup(rng).unwrap();
+        let (pk_buyer, sk_buyer) = HashedElGamal::<C>::keygen(&pp, rng).unwrap();
+        let r = Randomness::rand(rng);
+        let ct_k_data =
+            HashedElGamal::<C>::encrypt(&pp, &pk_buyer, &vec![register.k_data.unwrap()], &r)
+                .unwrap();
+
+        let price = F::from(1_000u64);
+        let order_opening = F::rand(rng);
+        let cm_order =
+            order_commitment(&rc, register.h_ct.unwrap(), &pk_buyer, price, order_opening).unwrap();
+
+        let settlement = Settlement {
+            rc: rc.round_constants,
+            h_k_data: register.h_k_data,
+            pk_seller: register.pk_peer_own,
+            h_ct: register.h_ct,
+            pk_buyer: Some(pk_buyer),
+            ct_k_data: Some(ct_k_data),
+            price: Some(price),
+            cm_order: Some(cm_order),
+            k_data: register.k_data,
+            ct_k_data_r: Some(r.0),
+            order_opening: Some(order_opening),
+            _curve_var: std::marker::PhantomData,
+        };
+        (settlement, sk_buyer)
+    }
+
+    #[test]
+    fn test_settlement() {
+        let (input, sk_buyer) = generate_settlement_input();
+
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let (pk, vk) = Groth16::<Bn254>::setup(input.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+
+        let ct_k_data = input.ct_k_data.clone().unwrap();
+        let mut image = vec![input.h_k_data.unwrap()];
+        image.extend(input.pk_seller.unwrap().to_field_elements().unwrap());
+        image.push(input.h_ct.unwrap());
+        image.extend(input.pk_buyer.unwrap().to_field_elements().unwrap());
+        image.extend(ct_k_data.c1.to_field_elements().unwrap());
+        image.extend(ct_k_data.c2.iter());
+        image.push(input.price.unwrap());
+        image.push(input.cm_order.unwrap());
+        assert!(Groth16::<Bn254>::verify(&vk, &image, &proof).unwrap());
+
+        // the buyer recovers the data key
+        let pp = HashedElGamal::<C>::setup(&mut rng).unwrap();
+        let k_data = HashedElGamal::<C>::decrypt(&pp, &sk_buyer, &ct_k_data).unwrap();
+        assert_eq!(k_data, vec![input.k_data.unwrap()]);
+    }
+
+    #[test]
+    fn test_settlement_with_other_price() {
+        let (mut input, _) = generate_settlement_input();
+        input.price = Some(F::from(1u64));
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_settlement_with_other_key() {
+        let rng = &mut test_rng();
+        let (mut input, _) = generate_settlement_input();
+        input.k_data = Some(F::rand(rng));
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_settlement_to_other_buyer() {
+        let rng = &mut test_rng();
+        let (mut input, _) = generate_settlement_input();
+
+        // k_data encrypted correctly, but to a key other than the one in the buyer's order
+        let pp = HashedElGamal::<C>::setup(rng).unwrap();
+        let (pk_other, _) = HashedElGamal::<C>::keygen(&pp, rng).unwrap();
+        let r = Randomness::rand(rng);
+        input.ct_k_data = Some(
+            HashedElGamal::<C>::encrypt(&pp, &pk_other, &vec![input.k_data.unwrap()], &r).unwrap(),
+        );
+        input.ct_k_data_r = Some(r.0);
+        input.pk_buyer = Some(pk_other);
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_settlement_key_decrypts_registered_ciphertext() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let register = generate_test_input(4).unwrap();
+        let (input, sk_buyer) = settle(&register);
+
+        // the key settled for the registered h_k_data decrypts the ciphertext behind h_ct
+        let pp = HashedElGamal::<C>::setup(rng).unwrap();
+        let k_data =
+            HashedElGamal::<C>::decrypt(&pp, &sk_buyer, &input.ct_k_data.unwrap()).unwrap()[0];
+        for (i, (c, m)) in register
+            .ct_data
+            .clone()
+            .unwrap()
+            .into_iter()
+            .zip(register.data.clone().unwrap())
+            .enumerate()
+        {
+            let plaintext = SymmetricEncryptionScheme::<F>::decrypt_block(
+                rc.clone(),
+                symmetric::SymmetricKey { k: k_data },
+                i as u64,
+                symmetric::Ciphertext {
+                    r: register.ct_r.unwrap(),
+                    c,
+                },
+            )
+            .unwrap();
+            assert_eq!(plaintext.m, m);
+        }
+
+        // settling another key needs its own h_k_data, which cannot be registered with h_ct
+        let other_key = F::rand(rng);
+        let mut hash_input = register.pk_peer_own.unwrap().to_field_elements().unwrap();
+        hash_input.push(other_key);
+        let mut forged = register;
+        forged.k_data = Some(other_key);
+        forged.h_k_data = Some(mimc7::MiMC::<F>::evaluate(&rc, hash_input).unwrap());
+
+        let cs = ConstraintSystem::<F>::new_ref();
+        forged.generate_constraints(cs.clone()).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_settlement_setup_without_assignment() {
+        let (input, _) = generate_settlement_input();
+        let cs = ConstraintSystem::<F>::new_ref();
+        input.clone().generate_constraints(cs.clone()).unwrap();
+
+        let blank = Settlement::<C, GG> {
+            rc: input.rc,
+            h_k_data: None,
+            pk_seller: None,
+            h_ct: None,
+            pk_buyer: None,
+            ct_k_data: None,
+            price: None,
+            cm_order: None,
+            k_data: None,
+            ct_k_data_r: None,
+            order_opening: None,
+            _curve_var: std::marker::PhantomData,
+        };
+        let setup_cs = ConstraintSystem::<F>::new_ref();
+        setup_cs.set_mode(SynthesisMode::Setup);
+        blank.generate_constraints(setup_cs.clone()).unwrap();
+
+        assert_eq!(
+            setup_cs.num_instance_variables(),
+            1 + 1 + 2 + 1 + 2 + 2 + CT_K_DATA_LEN + 2
+        );
+        assert_eq!(
+            setup_cs.num_instance_variables(),
+            cs.num_instance_variables()
+        );
+        assert_eq!(setup_cs.num_constraints(), cs.num_constraints());
+    }
+}
@@ -0,0 +1,116 @@
+//! Byte, hex and base64 encodings of arkworks types.
+//!
+//! Points are always compressed. Decoding validates the value (points must be on the curve and in
+//! the prime-order subgroup) and rejects trailing bytes.
+
+use crate::Error;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+pub fn to_bytes<T: CanonicalSerialize>(value: &T) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(value.compressed_size());
+    value.serialize_compressed(&mut bytes)?;
+    Ok(bytes)
+}
+
+pub fn from_bytes<T: CanonicalDeserialize>(bytes: &[u8]) -> Result<T, Error> {
+    let mut reader = bytes;
+    let value = T::deserialize_compressed(&mut reader)?;
+    if !reader.is_empty() {
+        return Err(format!("{} trailing bytes", reader.len()).into());
+    }
+    Ok(value)
+}
+
+pub fn to_hex<T: CanonicalSerialize>(value: &T) -> Result<String, Error> {
+    to_bytes(value).map(hex::encode)
+}
+
+pub fn from_hex<T: CanonicalDeserialize>(encoded: &str) -> Result<T, Error> {
+    from_bytes(&hex::decode(encoded)?)
+}
+
+pub fn to_base64<T: CanonicalSerialize>(value: &T) -> Result<String, Error> {
+    to_bytes(value).map(|bytes| STANDARD.encode(bytes))
+}
+
+pub fn from_base64<T: CanonicalDeserialize>(encoded: &str) -> Result<T, Error> {
+    from_bytes(&STANDARD.decode(encoded)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ed_on_bn254::{EdwardsAffine, EdwardsProjective, Fq};
+    use ark_ff::MontFp;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::{from_base64, from_bytes, from_hex, to_base64, to_bytes, to_hex};
+    use crate::gadget::public_encryptions::elgamal::{
+        Ciphertext, ElGamal, Parameters, Randomness, SecretKey,
+    };
+    use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+
+    type MyEnc = ElGamal<EdwardsProjective>;
+
+    #[test]
+    fn test_elgamal_roundtrip() {
+        let rng = &mut test_rng();
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+        let msg = EdwardsProjective::rand(rng).into_affine();
+        let r = Randomness::rand(rng);
+        let ciphertext = MyEnc::encrypt(&parameters, &pk, &msg, &r).unwrap();
+
+        assert_eq!(
+            parameters,
+            from_hex::<Parameters<EdwardsProjective>>(&to_hex(&parameters).unwrap()).unwrap()
+        );
+        assert_eq!(
+            pk,
+            from_base64::<EdwardsAffine>(&to_base64(&pk).unwrap()).unwrap()
+        );
+        assert_eq!(
+            sk,
+            from_hex::<SecretKey<EdwardsProjective>>(&to_hex(&sk).unwrap()).unwrap()
+        );
+        assert_eq!(
+            r,
+            from_base64::<Randomness<EdwardsProjective>>(&to_base64(&r).unwrap()).unwrap()
+        );
+        assert_eq!(
+            ciphertext,
+            from_hex::<Ciphertext<EdwardsProjective>>(&to_hex(&ciphertext).unwrap()).unwrap()
+        );
+
+        // points are compressed
+        assert_eq!(to_bytes(&pk).unwrap().len(), 32);
+    }
+
+    #[test]
+    fn test_invalid_encodings() {
+        let rng = &mut test_rng();
+        let pk = EdwardsProjective::rand(rng).into_affine();
+
+        // trailing bytes
+        let mut bytes = to_bytes(&pk).unwrap();
+        bytes.push(0);
+        assert!(from_bytes::<EdwardsAffine>(&bytes).is_err());
+
+        // malformed hex and base64
+        assert!(from_hex::<EdwardsAffine>("zz").is_err());
+        assert!(from_base64::<EdwardsAffine>("!!").is_err());
+
+        // (0, -1) is on the curve but has order 2, so it is not in the prime-order subgroup
+        let low_order = EdwardsAffine::new_unchecked(Fq::from(0u64), MontFp!("-1"));
+        assert!(low_order.is_on_curve());
+        assert!(!low_order.is_in_correct_subgroup_assuming_on_curve());
+        let mut bytes = Vec::new();
+        ark_serialize::CanonicalSerialize::serialize_compressed(&low_order, &mut bytes).unwrap();
+        assert!(from_bytes::<EdwardsAffine>(&bytes).is_err());
+        assert!(
+            from_bytes::<EdwardsAffine>(&to_bytes(&EdwardsAffine::generator()).unwrap()).is_ok()
+        );
+    }
+}
@@ -0,0 +1,30 @@
+use crate::gadget::commitments::CommitmentScheme;
+
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::SynthesisError;
+use core::fmt::Debug;
+
+use ark_ff::fields::Field;
+
+pub trait CommitmentGadget<C: CommitmentScheme, ConstraintF: Field> {
+    type ParametersVar: AllocVar<C::Parameters, ConstraintF> + Clone;
+    type InputVar: ?Sized;
+    type RandomnessVar: AllocVar<C::Randomness, ConstraintF> + Clone;
+    type OutputVar: AllocVar<C::Output, ConstraintF> + EqGadget<ConstraintF> + Clone + Sized + Debug;
+
+    fn commit(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+        r: &Self::RandomnessVar,
+    ) -> Result<Self::OutputVar, SynthesisError>;
+
+    /// Returns whether `commitment` opens to `input` with randomness `r`.
+    fn verify(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+        r: &Self::RandomnessVar,
+        commitment: &Self::OutputVar,
+    ) -> Result<Boolean<ConstraintF>, SynthesisError> {
+        Self::commit(parameters, input, r)?.is_eq(commitment)
+    }
+}
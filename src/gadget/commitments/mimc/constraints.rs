@@ -0,0 +1,97 @@
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+
+use crate::gadget::commitments::mimc::{MiMCCommitment, Randomness};
+use crate::gadget::commitments::CommitmentGadget;
+use crate::gadget::hashes::{
+    constraints::CRHSchemeGadget,
+    mimc7::constraints::{MiMCGadget, ParametersVar},
+};
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::PrimeField;
+use ark_std::{borrow::Borrow, marker::PhantomData};
+
+#[derive(Clone, Debug)]
+pub struct RandomnessVar<F: PrimeField>(pub FpVar<F>);
+
+impl<F: PrimeField> AllocVar<Randomness<F>, F> for RandomnessVar<F> {
+    fn new_variable<T: Borrow<Randomness<F>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        FpVar::new_variable(cs, || f().map(|r| r.borrow().0), mode).map(Self)
+    }
+}
+
+pub struct MiMCCommitmentGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F: PrimeField + Absorb> CommitmentGadget<MiMCCommitment<F>, F> for MiMCCommitmentGadget<F> {
+    type ParametersVar = ParametersVar<F>;
+    type InputVar = [FpVar<F>];
+    type RandomnessVar = RandomnessVar<F>;
+    type OutputVar = FpVar<F>;
+
+    fn commit(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+        r: &Self::RandomnessVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let len = FpVar::Constant(F::from(input.len() as u64));
+        let mut preimage = vec![len, r.0.clone()];
+        preimage.extend_from_slice(input);
+        MiMCGadget::<F>::evaluate(parameters, &preimage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::{MiMCCommitmentGadget, RandomnessVar};
+    use crate::gadget::commitments::mimc::{MiMCCommitment, Randomness};
+    use crate::gadget::commitments::{CommitmentGadget, CommitmentScheme};
+    use crate::gadget::hashes::mimc7::{self, constraints::ParametersVar};
+
+    #[test]
+    fn test_mimc_commitment_gadget() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+
+        let input = [Fr::rand(rng), Fr::rand(rng)];
+        let r = Randomness::rand(rng);
+        let commitment = MiMCCommitment::<Fr>::commit(&rc, &input, &r).unwrap();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let rc_var = ParametersVar::new_constant(ark_relations::ns!(cs, "rc"), &rc).unwrap();
+        let input_var = input
+            .iter()
+            .map(|x| FpVar::new_witness(ark_relations::ns!(cs, "input"), || Ok(x)).unwrap())
+            .collect::<Vec<_>>();
+        let r_var = RandomnessVar::new_witness(ark_relations::ns!(cs, "r"), || Ok(&r)).unwrap();
+        let commitment_var =
+            FpVar::new_input(ark_relations::ns!(cs, "commitment"), || Ok(commitment)).unwrap();
+
+        let opened =
+            MiMCCommitmentGadget::verify(&rc_var, &input_var, &r_var, &commitment_var).unwrap();
+        assert!(opened.value().unwrap());
+
+        // another opening does not verify
+        let other_r_var = RandomnessVar::new_witness(ark_relations::ns!(cs, "other_r"), || {
+            Ok(Randomness::rand(rng))
+        })
+        .unwrap();
+        let opened =
+            MiMCCommitmentGadget::verify(&rc_var, &input_var, &other_r_var, &commitment_var)
+                .unwrap();
+        assert!(!opened.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,76 @@
+pub mod constraints;
+
+use crate::gadget::commitments::CommitmentScheme;
+use crate::gadget::hashes::{mimc7, CRHScheme};
+use crate::Error;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::{PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::Rng;
+
+/// Hiding commitment `MiMC(len || r || input)` to field elements, blinded by a random field
+/// element `r`. Absorbing the number of inputs first binds the commitment to the input length.
+pub struct MiMCCommitment<F: PrimeField + Absorb> {
+    _field: PhantomData<F>,
+}
+
+#[derive(Clone, Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Randomness<F: PrimeField>(pub F);
+
+impl<F: PrimeField> UniformRand for Randomness<F> {
+    #[inline]
+    fn rand<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        Randomness(F::rand(rng))
+    }
+}
+
+impl<F: PrimeField + Absorb> CommitmentScheme for MiMCCommitment<F> {
+    type Parameters = mimc7::Parameters<F>;
+    type Input = [F];
+    type Randomness = Randomness<F>;
+    type Output = F;
+
+    fn commit(
+        pp: &Self::Parameters,
+        input: &Self::Input,
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        let mut preimage = vec![F::from(input.len() as u64), r.0];
+        preimage.extend_from_slice(input);
+        mimc7::MiMC::<F>::evaluate(pp, preimage)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    use crate::gadget::commitments::mimc::{MiMCCommitment, Randomness};
+    use crate::gadget::commitments::CommitmentScheme;
+    use crate::gadget::hashes::{mimc7, CRHScheme};
+
+    #[test]
+    fn test_mimc_commitment() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+
+        let input = [Fr::rand(rng), Fr::rand(rng)];
+        let r = Randomness::rand(rng);
+        let commitment = MiMCCommitment::<Fr>::commit(&rc, &input, &r).unwrap();
+        assert!(MiMCCommitment::<Fr>::verify(&rc, &input, &r, &commitment).unwrap());
+
+        // the same input with other randomness gives another commitment
+        let other_r = Randomness::rand(rng);
+        assert!(!MiMCCommitment::<Fr>::verify(&rc, &input, &other_r, &commitment).unwrap());
+        assert!(!MiMCCommitment::<Fr>::verify(&rc, &input[..1], &r, &commitment).unwrap());
+
+        // MiMC chains its inputs, so without the length `(r, [a, b])` and `(MiMC(r || a), [b])`
+        // would open the same commitment
+        let chained = Randomness(mimc7::MiMC::<Fr>::evaluate(&rc, [r.0, input[0]]).unwrap());
+        assert!(!MiMCCommitment::<Fr>::verify(&rc, &input[1..], &chained, &commitment).unwrap());
+    }
+}
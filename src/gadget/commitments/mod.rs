@@ -0,0 +1,30 @@
+pub mod constraints;
+pub use constraints::*;
+
+pub mod mimc;
+pub mod pedersen;
+
+use crate::Error;
+
+pub trait CommitmentScheme {
+    type Parameters;
+    type Input: ?Sized;
+    type Randomness;
+    type Output: PartialEq;
+
+    fn commit(
+        pp: &Self::Parameters,
+        input: &Self::Input,
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error>;
+
+    /// Checks that `commitment` opens to `input` with randomness `r`.
+    fn verify(
+        pp: &Self::Parameters,
+        input: &Self::Input,
+        r: &Self::Randomness,
+        commitment: &Self::Output,
+    ) -> Result<bool, Error> {
+        Ok(Self::commit(pp, input, r)? == *commitment)
+    }
+}
@@ -0,0 +1,162 @@
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use derivative::Derivative;
+
+use crate::gadget::commitments::pedersen::{Parameters, Pedersen};
+use crate::gadget::commitments::CommitmentGadget;
+use crate::gadget::public_encryptions::elgamal::constraints::{
+    scalar_mul_le, ConstraintF, RandomnessVar,
+};
+use ark_ec::CurveGroup;
+use ark_std::{borrow::Borrow, marker::PhantomData};
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>"))]
+pub struct ParametersVar<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub generator: GG,
+    pub blinding_generator: GG,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> AllocVar<Parameters<C>, ConstraintF<C>> for ParametersVar<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn new_variable<T: Borrow<Parameters<C>>>(
+        cs: impl Into<Namespace<ConstraintF<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let parameters = f();
+        let generator = GG::new_variable(
+            ark_relations::ns!(cs, "generator"),
+            || {
+                parameters
+                    .as_ref()
+                    .map(|p| p.borrow().generator)
+                    .map_err(|e| *e)
+            },
+            mode,
+        )?;
+        let blinding_generator = GG::new_variable(
+            ark_relations::ns!(cs, "blinding_generator"),
+            || {
+                parameters
+                    .as_ref()
+                    .map(|p| p.borrow().blinding_generator)
+                    .map_err(|e| *e)
+            },
+            mode,
+        )?;
+        Ok(Self {
+            generator,
+            blinding_generator,
+            _curve: PhantomData,
+        })
+    }
+}
+
+pub struct PedersenCommitmentGadget<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    _curve: PhantomData<*const C>,
+    _group_var: PhantomData<*const GG>,
+}
+
+impl<C, GG> CommitmentGadget<Pedersen<C>, ConstraintF<C>> for PedersenCommitmentGadget<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    type ParametersVar = ParametersVar<C, GG>;
+    /// Little-endian bits of the committed scalar.
+    type InputVar = [Boolean<ConstraintF<C>>];
+    type RandomnessVar = RandomnessVar<ConstraintF<C>>;
+    type OutputVar = GG;
+
+    fn commit(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+        r: &Self::RandomnessVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let value_part = scalar_mul_le::<C, GG>(&parameters.generator, input)?;
+        let blinding_part = scalar_mul_le::<C, GG>(&parameters.blinding_generator, &r.0)?;
+        Ok(value_part + blinding_part)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ec::CurveGroup;
+    use ark_ed_on_bn254::{constraints::EdwardsVar, EdwardsProjective, Fq, Fr};
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::{ParametersVar, PedersenCommitmentGadget};
+    use crate::gadget::commitments::pedersen::{Parameters, Pedersen};
+    use crate::gadget::commitments::{CommitmentGadget, CommitmentScheme};
+    use crate::gadget::public_encryptions::elgamal::{constraints::RandomnessVar, Randomness};
+
+    #[test]
+    fn test_pedersen_commitment_gadget() {
+        let rng = &mut test_rng();
+        let pp = Parameters::<EdwardsProjective>::standard();
+
+        let value = Fr::rand(rng);
+        let r = Randomness::rand(rng);
+        let commitment = Pedersen::<EdwardsProjective>::commit(&pp, &value, &r).unwrap();
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let pp_var = ParametersVar::<EdwardsProjective, EdwardsVar>::new_constant(
+            ark_relations::ns!(cs, "pp"),
+            &pp,
+        )
+        .unwrap();
+        let value_var = value
+            .into_bigint()
+            .to_bits_le()
+            .iter()
+            .take(Fr::MODULUS_BIT_SIZE as usize)
+            .map(|b| Boolean::new_witness(ark_relations::ns!(cs, "value_bit"), || Ok(*b)).unwrap())
+            .collect::<Vec<_>>();
+        let r_var = RandomnessVar::new_witness(ark_relations::ns!(cs, "r"), || Ok(&r)).unwrap();
+        let commitment_var =
+            EdwardsVar::new_input(ark_relations::ns!(cs, "commitment"), || Ok(commitment)).unwrap();
+
+        let opened = PedersenCommitmentGadget::<EdwardsProjective, EdwardsVar>::verify(
+            &pp_var,
+            &value_var,
+            &r_var,
+            &commitment_var,
+        )
+        .unwrap();
+        assert!(opened.value().unwrap());
+        assert_eq!(commitment_var.value().unwrap().into_affine(), commitment);
+
+        // another opening does not verify
+        let other_r_var = RandomnessVar::new_witness(ark_relations::ns!(cs, "other_r"), || {
+            Ok(Randomness::<EdwardsProjective>::rand(rng))
+        })
+        .unwrap();
+        let opened = PedersenCommitmentGadget::<EdwardsProjective, EdwardsVar>::verify(
+            &pp_var,
+            &value_var,
+            &other_r_var,
+            &commitment_var,
+        )
+        .unwrap();
+        assert!(!opened.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+}
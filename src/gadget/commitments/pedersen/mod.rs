@@ -0,0 +1,127 @@
+pub mod constraints;
+
+use crate::gadget::commitments::CommitmentScheme;
+use crate::gadget::public_encryptions::elgamal::Randomness;
+use crate::Error;
+use ark_crypto_primitives::crh::sha256::{digest::Digest, Sha256};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::ops::Mul;
+use derivative::Derivative;
+
+/// Domain tag hashed to the curve to derive the blinding generator `H`.
+pub const BLINDING_GENERATOR_TAG: &[u8] = b"datatrade-pedersen-h";
+
+/// Pedersen commitment `v*G + r*H` to a scalar `v`.
+pub struct Pedersen<C: CurveGroup> {
+    _group: PhantomData<C>,
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
+pub struct Parameters<C: CurveGroup> {
+    pub generator: C::Affine,
+    pub blinding_generator: C::Affine,
+}
+
+impl<C: CurveGroup> Parameters<C> {
+    /// Parameters using the curve's standard generator for `G` and a hash-to-curve of
+    /// [`BLINDING_GENERATOR_TAG`] for `H`, so that nobody knows the discrete log of `H` to `G`.
+    pub fn standard() -> Self {
+        Self {
+            generator: C::generator().into(),
+            blinding_generator: hash_to_curve::<C>(BLINDING_GENERATOR_TAG),
+        }
+    }
+}
+
+/// Try-and-increment hash to the prime-order subgroup: `SHA256(tag || counter)` is decoded as a
+/// point until it lands on the curve and is not cleared to the identity.
+fn hash_to_curve<C: CurveGroup>(tag: &[u8]) -> C::Affine {
+    (0u64..)
+        .find_map(|counter| {
+            let digest = Sha256::new()
+                .chain_update(tag)
+                .chain_update(counter.to_le_bytes())
+                .finalize();
+            C::Affine::from_random_bytes(&digest)
+                .map(|p| p.clear_cofactor())
+                .filter(|p| !p.is_zero())
+        })
+        .unwrap()
+}
+
+impl<C: CurveGroup> CommitmentScheme for Pedersen<C> {
+    type Parameters = Parameters<C>;
+    type Input = C::ScalarField;
+    type Randomness = Randomness<C>;
+    type Output = C::Affine;
+
+    fn commit(
+        pp: &Self::Parameters,
+        input: &Self::Input,
+        r: &Self::Randomness,
+    ) -> Result<Self::Output, Error> {
+        Ok((pp.generator.mul(*input) + pp.blinding_generator.mul(r.0)).into_affine())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_ec::CurveGroup;
+    use ark_ed_on_bn254::{EdwardsProjective, Fr};
+    use ark_std::{test_rng, UniformRand};
+
+    use crate::gadget::commitments::pedersen::{Parameters, Pedersen};
+    use crate::gadget::commitments::CommitmentScheme;
+    use crate::gadget::public_encryptions::elgamal::Randomness;
+
+    #[test]
+    fn test_pedersen_parameters() {
+        let pp = Parameters::<EdwardsProjective>::standard();
+        assert_eq!(pp, Parameters::<EdwardsProjective>::standard());
+        assert_ne!(pp.generator, pp.blinding_generator);
+        assert!(pp.blinding_generator.is_on_curve());
+        assert!(pp
+            .blinding_generator
+            .is_in_correct_subgroup_assuming_on_curve());
+    }
+
+    #[test]
+    fn test_pedersen_commitment() {
+        let rng = &mut test_rng();
+        let pp = Parameters::<EdwardsProjective>::standard();
+
+        let value = Fr::rand(rng);
+        let r = Randomness::rand(rng);
+        let commitment = Pedersen::<EdwardsProjective>::commit(&pp, &value, &r).unwrap();
+        assert!(Pedersen::<EdwardsProjective>::verify(&pp, &value, &r, &commitment).unwrap());
+        assert!(
+            !Pedersen::<EdwardsProjective>::verify(&pp, &Fr::rand(rng), &r, &commitment).unwrap()
+        );
+        assert!(!Pedersen::<EdwardsProjective>::verify(
+            &pp,
+            &value,
+            &Randomness::rand(rng),
+            &commitment
+        )
+        .unwrap());
+
+        // commitments are additively homomorphic
+        let other_value = Fr::rand(rng);
+        let other_r = Randomness::rand(rng);
+        let other = Pedersen::<EdwardsProjective>::commit(&pp, &other_value, &other_r).unwrap();
+        let sum = Pedersen::<EdwardsProjective>::commit(
+            &pp,
+            &(value + other_value),
+            &Randomness(r.0 + other_r.0),
+        )
+        .unwrap();
+        assert_eq!(sum, (commitment + other).into_affine());
+    }
+}
@@ -3,9 +3,10 @@ use crate::gadget::hashes::constraints::CRHSchemeGadget;
 use crate::gadget::merkle_tree::{Config, IdentityDigestConverter};
 use crate::gadget::merkle_tree::Path;
 
-use ark_ff::Field;
+use ark_ff::{BigInteger, Field, PrimeField};
 use ark_r1cs_std::alloc::AllocVar;
 use ark_r1cs_std::boolean::Boolean;
+use ark_r1cs_std::fields::fp::FpVar;
 #[allow(unused)]
 use ark_r1cs_std::prelude::*;
 use ark_r1cs_std::ToBytesGadget;
@@ -154,7 +155,13 @@ impl<P: Config, ConstraintF: Field, PG: ConfigGadget<P, ConstraintF>> PathVar<P,
     /// Set the leaf index of the path to a given value. Verifier can use function before calling `verify`
     /// to check the correctness leaf position.
     /// * `leaf_index`: leaf index encoded in little-endian format
-    pub fn set_leaf_position(&mut self, leaf_index: Vec<Boolean<ConstraintF>>) {
+    ///
+    /// Bits beyond the height of the tree are enforced to be zero, so an index that does not fit in the tree
+    /// cannot be satisfied by the path of another leaf.
+    pub fn set_leaf_position(
+        &mut self,
+        leaf_index: Vec<Boolean<ConstraintF>>,
+    ) -> Result<(), SynthesisError> {
         // The path to a leaf is described by the branching
         // decisions taken at each node. This corresponds to the position
         // of the leaf.
@@ -170,14 +177,17 @@ impl<P: Config, ConstraintF: Field, PG: ConfigGadget<P, ConstraintF>> PathVar<P,
             path.extend((0..self.auth_path.len() - path.len()).map(|_| Boolean::constant(false)))
         }
 
-        // truncate if the length of `path` is too long
-        path.truncate(self.auth_path.len());
+        // the bits that do not fit in the tree must be zero
+        for bit in path.drain(self.auth_path.len()..) {
+            bit.enforce_equal(&Boolean::FALSE)?;
+        }
 
         // branching decision starts from root, so we need to reverse it.
         path.reverse();
 
         self.path = path;
         self.leaf_is_right_child = leaf_is_right_child;
+        Ok(())
     }
 
     /// Return the leaf position index in little-endian form.
@@ -276,3 +286,150 @@ impl<P: Config, ConstraintF: Field, PG: ConfigGadget<P, ConstraintF>> PathVar<P,
         Ok(actual_new_root.is_eq(&new_root)?)
     }
 }
+
+impl<P: Config, ConstraintF: PrimeField, PG: ConfigGadget<P, ConstraintF>>
+    PathVar<P, ConstraintF, PG>
+{
+    /// Bind the path to the leaf index `leaf_index`, e.g. a public input of the circuit.
+    ///
+    /// `leaf_index` is decomposed into as many bits as the tree has levels below the root, and the
+    /// decomposition is enforced to equal `leaf_index`, so any index that does not fit in the tree is rejected.
+    pub fn set_leaf_index(
+        &mut self,
+        leaf_index: &FpVar<ConstraintF>,
+    ) -> Result<(), SynthesisError> {
+        let num_bits = self.auth_path.len() + 1;
+        assert!(
+            num_bits < ConstraintF::MODULUS_BIT_SIZE as usize,
+            "tree is too high for its leaf index to fit in a field element"
+        );
+
+        let bits = match leaf_index {
+            FpVar::Constant(index) => {
+                let index = index.into_bigint();
+                (0..num_bits)
+                    .map(|i| Boolean::constant(index.get_bit(i)))
+                    .collect::<Vec<_>>()
+            }
+            FpVar::Var(index) => {
+                let cs = index.cs.clone();
+                let value = index.value().map(|v| v.into_bigint());
+                (0..num_bits)
+                    .map(|i| {
+                        Boolean::new_witness(ark_relations::ns!(cs, "leaf_index_bit"), || {
+                            value.map(|v| v.get_bit(i))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?
+            }
+        };
+        Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(leaf_index)?;
+
+        self.set_leaf_position(bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    use crate::gadget::hashes::mimc7::{self, constraints::ParametersVar, parameters};
+    use crate::gadget::merkle_tree::{
+        constraints::PathVar,
+        mimc::{constraints::MiMCMerkleTreeConfigGadget, MiMCMerkleTreeConfig},
+        MerkleTree,
+    };
+
+    type FieldMT = MerkleTree<MiMCMerkleTreeConfig<Fr>>;
+    type FieldPathVar = PathVar<MiMCMerkleTreeConfig<Fr>, Fr, MiMCMerkleTreeConfigGadget<Fr>>;
+
+    /// Returns whether the path of `proof_index` verifies `leaves[proof_index]` when bound to the public `claimed_index`.
+    fn verify_with_index(
+        tree: &FieldMT,
+        leaves: &[Vec<Fr>],
+        proof_index: usize,
+        claimed_index: Fr,
+    ) -> bool {
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let proof = tree.generate_proof(proof_index).unwrap();
+
+        let rc_var =
+            ParametersVar::new_constant(ark_relations::ns!(cs, "round constants"), &rc).unwrap();
+        let root_var =
+            FpVar::new_input(ark_relations::ns!(cs, "root"), || Ok(tree.root())).unwrap();
+        let index_var =
+            FpVar::new_input(ark_relations::ns!(cs, "leaf_index"), || Ok(claimed_index)).unwrap();
+        let leaf_var: Vec<_> = leaves[proof_index]
+            .iter()
+            .map(|l| FpVar::new_witness(ark_relations::ns!(cs, "leaf"), || Ok(l)).unwrap())
+            .collect();
+        let mut path_var =
+            FieldPathVar::new_witness(ark_relations::ns!(cs, "path"), || Ok(&proof)).unwrap();
+
+        path_var.set_leaf_index(&index_var).unwrap();
+        path_var
+            .verify_membership(&rc_var, &rc_var, &root_var, &leaf_var)
+            .unwrap()
+            .enforce_equal(&Boolean::TRUE)
+            .unwrap();
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn test_leaf_index_range_check() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        let leaves: Vec<Vec<Fr>> = (0..8).map(|_| vec![Fr::rand(rng)]).collect();
+        let tree = FieldMT::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+
+        assert!(verify_with_index(&tree, &leaves, 5, Fr::from(5u64)));
+        // the path of leaf 5 does not open another index
+        assert!(!verify_with_index(&tree, &leaves, 5, Fr::from(4u64)));
+        // indices sharing the low bits of 5 but outside of the tree are rejected
+        assert!(!verify_with_index(&tree, &leaves, 5, Fr::from(5u64 + 8)));
+        assert!(!verify_with_index(
+            &tree,
+            &leaves,
+            5,
+            Fr::from(5u64) - Fr::from(1u64 << 60)
+        ));
+    }
+
+    #[test]
+    fn test_set_leaf_position_rejects_high_bits() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        let leaves: Vec<Vec<Fr>> = (0..4).map(|_| vec![Fr::rand(rng)]).collect();
+        let tree = FieldMT::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+        let proof = tree.generate_proof(1).unwrap();
+
+        for (bits, satisfied) in [
+            (vec![true, false], true),
+            (vec![true], true),
+            (vec![true, false, false], true),
+            (vec![true, false, true], false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let mut path_var =
+                FieldPathVar::new_witness(ark_relations::ns!(cs, "path"), || Ok(&proof)).unwrap();
+            let bits = bits
+                .into_iter()
+                .map(|b| Boolean::new_input(ark_relations::ns!(cs, "bit"), || Ok(b)).unwrap())
+                .collect();
+            path_var.set_leaf_position(bits).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::marker::PhantomData;
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::PrimeField;
+use ark_r1cs_std::fields::{fp::FpVar, FieldVar};
+use ark_relations::r1cs::SynthesisError;
+
+use super::{InnerMiMC, LeafMiMC, MiMCMerkleTreeConfig, INNER_DOMAIN_TAG, LEAF_DOMAIN_TAG};
+use crate::gadget::hashes::constraints::{CRHSchemeGadget, TwoToOneCRHSchemeGadget};
+use crate::gadget::hashes::mimc7::constraints::{MiMCGadget, ParametersVar};
+use crate::gadget::merkle_tree::constraints::ConfigGadget;
+use crate::gadget::merkle_tree::IdentityDigestConverter;
+
+pub struct LeafMiMCGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F> CRHSchemeGadget<LeafMiMC<F>, F> for LeafMiMCGadget<F>
+where
+    F: PrimeField + Absorb,
+{
+    type ParametersVar = ParametersVar<F>;
+    type InputVar = [FpVar<F>];
+    type OutputVar = FpVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let mut tagged = vec![FpVar::constant(F::from(LEAF_DOMAIN_TAG))];
+        tagged.extend_from_slice(input);
+        MiMCGadget::<F>::evaluate(parameters, &tagged)
+    }
+}
+
+pub struct InnerMiMCGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F> TwoToOneCRHSchemeGadget<InnerMiMC<F>, F> for InnerMiMCGadget<F>
+where
+    F: PrimeField + Absorb,
+{
+    type ParametersVar = ParametersVar<F>;
+    type InputVar = FpVar<F>;
+    type OutputVar = FpVar<F>;
+
+    fn evaluate(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::InputVar,
+        right_input: &Self::InputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        let input = [
+            FpVar::constant(F::from(INNER_DOMAIN_TAG)),
+            left_input.clone(),
+            right_input.clone(),
+        ];
+        MiMCGadget::<F>::evaluate(parameters, &input)
+    }
+
+    fn compress(
+        parameters: &Self::ParametersVar,
+        left_input: &Self::OutputVar,
+        right_input: &Self::OutputVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        <Self as TwoToOneCRHSchemeGadget<InnerMiMC<F>, F>>::evaluate(
+            parameters,
+            left_input,
+            right_input,
+        )
+    }
+}
+
+pub struct MiMCMerkleTreeConfigGadget<F: PrimeField> {
+    _field: PhantomData<F>,
+}
+
+impl<F> ConfigGadget<MiMCMerkleTreeConfig<F>, F> for MiMCMerkleTreeConfigGadget<F>
+where
+    F: PrimeField + Absorb,
+{
+    type Leaf = [FpVar<F>];
+    type LeafDigest = FpVar<F>;
+    type LeafInnerConverter = IdentityDigestConverter<FpVar<F>>;
+    type InnerDigest = FpVar<F>;
+    type LeafHash = LeafMiMCGadget<F>;
+    type TwoToOneHash = InnerMiMCGadget<F>;
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, UniformRand};
+
+    use crate::gadget::hashes::mimc7::{self, constraints::ParametersVar, parameters};
+    use crate::gadget::merkle_tree::{constraints::PathVar, MerkleTree};
+
+    use super::{MiMCMerkleTreeConfig, MiMCMerkleTreeConfigGadget};
+
+    type FieldMT = MerkleTree<MiMCMerkleTreeConfig<Fr>>;
+    type FieldPathVar = PathVar<MiMCMerkleTreeConfig<Fr>, Fr, MiMCMerkleTreeConfigGadget<Fr>>;
+
+    #[test]
+    fn test_padded_tree_membership_gadget() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        let leaves: Vec<Vec<Fr>> = (0..5).map(|_| vec![Fr::rand(rng), Fr::rand(rng)]).collect();
+        let tree = FieldMT::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+
+        for (i, leaf) in leaves.iter().enumerate() {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let proof = tree.generate_proof(i).unwrap();
+
+            let rc_var =
+                ParametersVar::new_constant(ark_relations::ns!(cs, "round constants"), &rc)
+                    .unwrap();
+            let root_var =
+                FpVar::new_input(ark_relations::ns!(cs, "root"), || Ok(tree.root())).unwrap();
+            let leaf_var: Vec<_> = leaf
+                .iter()
+                .map(|l| FpVar::new_witness(ark_relations::ns!(cs, "leaf"), || Ok(l)).unwrap())
+                .collect();
+            let path_var =
+                FieldPathVar::new_witness(ark_relations::ns!(cs, "path"), || Ok(&proof)).unwrap();
+
+            path_var
+                .verify_membership(&rc_var, &rc_var, &root_var, &leaf_var)
+                .unwrap()
+                .enforce_equal(&Boolean::TRUE)
+                .unwrap();
+            assert!(cs.is_satisfied().unwrap());
+
+            // the padding leaf next to the last real leaf cannot be opened with a real leaf value
+            if i == leaves.len() - 1 {
+                let cs = ConstraintSystem::<Fr>::new_ref();
+                let padding_proof = tree.generate_proof(i + 1).unwrap();
+                let rc_var =
+                    ParametersVar::new_constant(ark_relations::ns!(cs, "round constants"), &rc)
+                        .unwrap();
+                let root_var =
+                    FpVar::new_input(ark_relations::ns!(cs, "root"), || Ok(tree.root())).unwrap();
+                let leaf_var: Vec<_> = leaf
+                    .iter()
+                    .map(|l| FpVar::new_witness(ark_relations::ns!(cs, "leaf"), || Ok(l)).unwrap())
+                    .collect();
+                let path_var = FieldPathVar::new_witness(ark_relations::ns!(cs, "path"), || {
+                    Ok(&padding_proof)
+                })
+                .unwrap();
+                assert!(!path_var
+                    .verify_membership(&rc_var, &rc_var, &root_var, &leaf_var)
+                    .unwrap()
+                    .value()
+                    .unwrap());
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::{borrow::Borrow, marker::PhantomData};
+
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::PrimeField;
+
+use crate::gadget::hashes::mimc7::{self, MiMC};
+use crate::gadget::hashes::{CRHScheme, TwoToOneCRHScheme};
+use crate::gadget::merkle_tree::{Config, IdentityDigestConverter};
+use crate::Error;
+
+pub mod constraints;
+
+/// Prepended to the input of every leaf hash.
+pub const LEAF_DOMAIN_TAG: u64 = 0;
+/// Prepended to the input of every inner hash.
+pub const INNER_DOMAIN_TAG: u64 = 1;
+
+/// Leaf hash of [`MiMCMerkleTreeConfig`]: `MiMC(LEAF_DOMAIN_TAG || leaf)`.
+pub struct LeafMiMC<F: PrimeField + Absorb> {
+    _field: PhantomData<F>,
+}
+
+impl<F> CRHScheme for LeafMiMC<F>
+where
+    F: PrimeField + Absorb,
+{
+    type Parameters = mimc7::Parameters<F>;
+    type Input = [F];
+    type Output = F;
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        input: T,
+    ) -> Result<Self::Output, Error> {
+        let mut tagged = vec![F::from(LEAF_DOMAIN_TAG)];
+        tagged.extend_from_slice(input.borrow());
+        MiMC::<F>::evaluate(parameters, tagged)
+    }
+}
+
+/// Inner hash of [`MiMCMerkleTreeConfig`]: `MiMC(INNER_DOMAIN_TAG || left || right)`.
+pub struct InnerMiMC<F: PrimeField + Absorb> {
+    _field: PhantomData<F>,
+}
+
+impl<F> TwoToOneCRHScheme for InnerMiMC<F>
+where
+    F: PrimeField + Absorb,
+{
+    type Parameters = mimc7::Parameters<F>;
+    type Input = F;
+    type Output = F;
+
+    fn evaluate<T: Borrow<Self::Input>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        MiMC::<F>::evaluate(
+            parameters,
+            [
+                F::from(INNER_DOMAIN_TAG),
+                *left_input.borrow(),
+                *right_input.borrow(),
+            ],
+        )
+    }
+
+    fn compress<T: Borrow<Self::Output>>(
+        parameters: &Self::Parameters,
+        left_input: T,
+        right_input: T,
+    ) -> Result<Self::Output, Error> {
+        <Self as TwoToOneCRHScheme>::evaluate(parameters, left_input.borrow(), right_input.borrow())
+    }
+}
+
+/// Merkle tree over field elements hashed with MiMC.
+///
+/// Leaf and inner hashes are domain separated, so a leaf can never be mistaken for an inner node
+/// and trees of any number of leaves can be padded safely.
+pub struct MiMCMerkleTreeConfig<F: PrimeField + Absorb> {
+    _field: PhantomData<F>,
+}
+
+impl<F> Config for MiMCMerkleTreeConfig<F>
+where
+    F: PrimeField + Absorb,
+{
+    type Leaf = [F];
+    type LeafDigest = F;
+    type LeafInnerDigestConverter = IdentityDigestConverter<F>;
+    type InnerDigest = F;
+
+    type LeafHash = LeafMiMC<F>;
+    type TwoToOneHash = InnerMiMC<F>;
+
+    const DOMAIN_SEPARATED: bool = true;
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    use crate::gadget::hashes::mimc7::{self, parameters};
+    use crate::gadget::hashes::TwoToOneCRHScheme;
+    use crate::gadget::merkle_tree::{MerkleTree, Path};
+
+    use super::{InnerMiMC, MiMCMerkleTreeConfig};
+
+    type FieldMT = MerkleTree<MiMCMerkleTreeConfig<Fr>>;
+
+    #[test]
+    fn test_non_power_of_two_tree() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        for num_leaves in [1, 3, 5, 6, 7, 9] {
+            let leaves: Vec<Vec<Fr>> = (0..num_leaves).map(|_| vec![Fr::rand(rng)]).collect();
+            let tree = FieldMT::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+            assert_eq!(tree.height(), 1 + ark_std::log2(num_leaves.max(2)) as usize);
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = tree.generate_proof(i).unwrap();
+                assert!(proof
+                    .verify(&rc, &rc, &tree.root(), leaf.as_slice())
+                    .unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_inner_node_is_not_a_leaf() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        let leaves: Vec<Vec<Fr>> = (0..4).map(|_| vec![Fr::rand(rng)]).collect();
+        let tree = FieldMT::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+
+        // claim the left bottom inner node as a leaf of a tree one level shorter
+        let proof = tree.generate_proof(0).unwrap();
+        let left = tree.generate_proof(2).unwrap().auth_path[0];
+        let right = proof.auth_path[0];
+        let forged = Path::<MiMCMerkleTreeConfig<Fr>> {
+            leaf_sibling_hash: right,
+            auth_path: Vec::new(),
+            leaf_index: 0,
+        };
+        assert_eq!(
+            InnerMiMC::<Fr>::evaluate(&rc, left, right).unwrap(),
+            tree.root()
+        );
+        // the claimed leaf hashes to something else than the inner node, so verification fails
+        assert!(!forged
+            .verify(&rc, &rc, &tree.root(), [left].as_slice())
+            .unwrap());
+    }
+
+    #[test]
+    fn test_leaf_index_out_of_range() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        let leaves: Vec<Vec<Fr>> = (0..4).map(|_| vec![Fr::rand(rng)]).collect();
+        let tree = FieldMT::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+        let mut proof = tree.generate_proof(1).unwrap();
+        proof.leaf_index += 4;
+        assert!(!proof
+            .verify(&rc, &rc, &tree.root(), leaves[1].as_slice())
+            .unwrap());
+    }
+}
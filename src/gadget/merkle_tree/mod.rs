@@ -6,11 +6,20 @@ use crate::gadget::hashes::CRHScheme;
 use ark_crypto_primitives::Error;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::borrow::Borrow;
+use ark_std::collections::{BTreeMap, BTreeSet};
 use ark_std::hash::Hash;
 use ark_std::vec::Vec;
 use derivative::Derivative;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 pub mod constraints;
+pub mod mimc;
+pub mod storage;
+
+pub use storage::{
+    FileStorage, MemoryStorage, MerkleTreeStorage, RootHistory, DEFAULT_ROOT_HISTORY_SIZE,
+};
 
 /// Convert the hash digest in different layers by converting previous layer's output to
 /// `TargetType`, which is a `Borrow` to next layer's input.
@@ -84,6 +93,10 @@ pub trait Config {
     type LeafHash: CRHScheme<Input = Self::Leaf, Output = Self::LeafDigest>;
     /// 2 inner digest -> inner digest
     type TwoToOneHash: TwoToOneCRHScheme<Output = Self::InnerDigest>;
+
+    /// Whether the leaf hash and the two to one hash are domain separated, so that a leaf can
+    /// never be mistaken for an inner node. Only such trees may be padded with empty leaves.
+    const DOMAIN_SEPARATED: bool = false;
 }
 
 pub type TwoToOneParam<P> = <<P as Config>::TwoToOneHash as TwoToOneCRHScheme>::Parameters;
@@ -140,6 +153,11 @@ impl<P: Config> Path<P> {
         root_hash: &P::InnerDigest,
         leaf: L,
     ) -> Result<bool, crate::Error> {
+        // the leaf index must address a leaf of a tree of this height
+        if self.leaf_index >> (self.auth_path.len() + 1) != 0 {
+            return Ok(false);
+        }
+
         // calculate leaf hash
         let claimed_leaf_hash = P::LeafHash::evaluate(&leaf_hash_params, leaf)?;
         // check hash along the path from bottom to root
@@ -198,26 +216,27 @@ fn select_left_right_child<L: Clone>(
 }
 
 /// Defines a merkle tree data structure.
-/// This merkle tree has runtime fixed height, and assumes number of leaves is 2^height.
+/// This merkle tree has runtime fixed height. If [`Config::DOMAIN_SEPARATED`] is set, the leaves
+/// are padded with empty leaf digests up to the next power of two, otherwise their number should
+/// be a power of two.
+/// The nodes live in a [`MerkleTreeStorage`] backend, which keeps everything in memory by default.
 ///
-/// TODO: add RFC-6962 compatible merkle tree in the future.
-/// For this release, padding will not be supported because of security concerns: if the leaf hash and two to one hash uses same underlying
-/// CRH, a malicious prover can prove a leaf while the actual node is an inner node. In the future, we can prefix leaf hashes in different layers to
-/// solve the problem.
+/// Padding is only safe if the leaf hash and two to one hash are domain separated: if they use the same underlying
+/// CRH, a malicious prover can prove a leaf while the actual node is an inner node. [`mimc::MiMCMerkleTreeConfig`]
+/// prefixes leaf and inner hash inputs with different tags to solve the problem.
 #[derive(Derivative)]
-#[derivative(Clone(bound = "P: Config"))]
-pub struct MerkleTree<P: Config> {
-    /// stores the non-leaf nodes in level order. The first element is the root node.
-    /// The ith nodes (starting at 1st) children are at indices `2*i`, `2*i+1`
-    non_leaf_nodes: Vec<P::InnerDigest>,
-    /// store the hash of leaf nodes from left to right
-    leaf_nodes: Vec<P::LeafDigest>,
+#[derivative(Clone(bound = "P: Config, S: Clone"))]
+pub struct MerkleTree<P: Config, S: MerkleTreeStorage<P> = MemoryStorage<P>> {
+    /// stores the non-leaf nodes in level order and the leaf nodes from left to right
+    storage: S,
     /// Store the inner hash parameters
     two_to_one_hash_param: TwoToOneParam<P>,
     /// Store the leaf hash parameters
     leaf_hash_param: LeafParam<P>,
     /// Stores the height of the MerkleTree
     height: usize,
+    /// Stores the most recent roots, including the current one
+    root_history: RootHistory<P>,
 }
 
 impl<P: Config> MerkleTree<P> {
@@ -227,14 +246,65 @@ impl<P: Config> MerkleTree<P> {
         leaf_hash_param: &LeafParam<P>,
         two_to_one_hash_param: &TwoToOneParam<P>,
         height: usize,
+    ) -> Result<Self, crate::Error> {
+        Self::blank_with_storage(
+            MemoryStorage::default(),
+            leaf_hash_param,
+            two_to_one_hash_param,
+            height,
+        )
+    }
+
+    /// Returns a new merkle tree. `leaves` is padded up to a power of two if the config is domain
+    /// separated, otherwise `leaves.len()` should be a power of two.
+    pub fn new<L: Borrow<P::Leaf>>(
+        leaf_hash_param: &LeafParam<P>,
+        two_to_one_hash_param: &TwoToOneParam<P>,
+        leaves: impl IntoIterator<Item = L>,
+    ) -> Result<Self, crate::Error> {
+        Self::new_with_storage(
+            MemoryStorage::default(),
+            leaf_hash_param,
+            two_to_one_hash_param,
+            leaves,
+        )
+    }
+
+    pub fn new_with_leaf_digest(
+        leaf_hash_param: &LeafParam<P>,
+        two_to_one_hash_param: &TwoToOneParam<P>,
+        leaves_digest: Vec<P::LeafDigest>,
+    ) -> Result<Self, crate::Error> {
+        Self::new_with_leaf_digest_and_storage(
+            MemoryStorage::default(),
+            leaf_hash_param,
+            two_to_one_hash_param,
+            leaves_digest,
+        )
+    }
+}
+
+impl<P: Config, S: MerkleTreeStorage<P>> MerkleTree<P, S> {
+    /// Same as [`MerkleTree::blank`], but the nodes are written to `storage`.
+    pub fn blank_with_storage(
+        storage: S,
+        leaf_hash_param: &LeafParam<P>,
+        two_to_one_hash_param: &TwoToOneParam<P>,
+        height: usize,
     ) -> Result<Self, crate::Error> {
         // use empty leaf digest
         let leaves_digest = vec![P::LeafDigest::default(); 1 << (height - 1)];
-        Self::new_with_leaf_digest(leaf_hash_param, two_to_one_hash_param, leaves_digest)
+        Self::new_with_leaf_digest_and_storage(
+            storage,
+            leaf_hash_param,
+            two_to_one_hash_param,
+            leaves_digest,
+        )
     }
 
-    /// Returns a new merkle tree. `leaves.len()` should be power of two.
-    pub fn new<L: Borrow<P::Leaf>>(
+    /// Same as [`MerkleTree::new`], but the nodes are written to `storage`.
+    pub fn new_with_storage<L: Borrow<P::Leaf>>(
+        storage: S,
         leaf_hash_param: &LeafParam<P>,
         two_to_one_hash_param: &TwoToOneParam<P>,
         leaves: impl IntoIterator<Item = L>,
@@ -246,19 +316,31 @@ impl<P: Config> MerkleTree<P> {
             leaves_digests.push(P::LeafHash::evaluate(leaf_hash_param, leaf)?)
         }
 
-        Self::new_with_leaf_digest(leaf_hash_param, two_to_one_hash_param, leaves_digests)
+        Self::new_with_leaf_digest_and_storage(
+            storage,
+            leaf_hash_param,
+            two_to_one_hash_param,
+            leaves_digests,
+        )
     }
 
-    pub fn new_with_leaf_digest(
+    /// Same as [`MerkleTree::new_with_leaf_digest`], but the nodes are written to `storage`.
+    /// Any node previously held by `storage` is discarded.
+    pub fn new_with_leaf_digest_and_storage(
+        mut storage: S,
         leaf_hash_param: &LeafParam<P>,
         two_to_one_hash_param: &TwoToOneParam<P>,
-        leaves_digest: Vec<P::LeafDigest>,
+        mut leaves_digest: Vec<P::LeafDigest>,
     ) -> Result<Self, crate::Error> {
-        let leaf_nodes_size = leaves_digest.len();
+        assert!(!leaves_digest.is_empty(), "`leaves` should not be empty");
         assert!(
-            leaf_nodes_size.is_power_of_two() && leaf_nodes_size > 1,
+            P::DOMAIN_SEPARATED
+                || (leaves_digest.len().is_power_of_two() && leaves_digest.len() > 1),
             "`leaves.len() should be power of two and greater than one"
         );
+        // pad with empty leaves so that the tree is complete
+        let leaf_nodes_size = leaves_digest.len().next_power_of_two().max(2);
+        leaves_digest.resize(leaf_nodes_size, P::LeafDigest::default());
         let non_leaf_nodes_size = leaf_nodes_size - 1;
 
         let tree_height = tree_height(leaf_nodes_size);
@@ -313,18 +395,61 @@ impl<P: Config> MerkleTree<P> {
             }
         }
 
+        let mut root_history = RootHistory::new(DEFAULT_ROOT_HISTORY_SIZE);
+        root_history.push(non_leaf_nodes[0].clone());
+
+        // write the nodes to the storage
+        storage.reset(leaf_nodes_size)?;
+        for (index, digest) in leaves_digest.into_iter().enumerate() {
+            storage.set_leaf(index, digest)?;
+        }
+        for (index, digest) in non_leaf_nodes.into_iter().enumerate() {
+            storage.set_inner(index, digest)?;
+        }
+        storage.set_root_history(&root_history)?;
+
         Ok(MerkleTree {
-            leaf_nodes: leaves_digest,
-            non_leaf_nodes,
+            storage,
             height: tree_height,
             leaf_hash_param: leaf_hash_param.clone(),
             two_to_one_hash_param: two_to_one_hash_param.clone(),
+            root_history,
+        })
+    }
+
+    /// Load a merkle tree previously written to `storage`.
+    pub fn open(
+        storage: S,
+        leaf_hash_param: &LeafParam<P>,
+        two_to_one_hash_param: &TwoToOneParam<P>,
+    ) -> Result<Self, crate::Error> {
+        let leaf_nodes_size = storage.num_leaves();
+        if !leaf_nodes_size.is_power_of_two() || leaf_nodes_size < 2 {
+            return Err("storage does not hold a merkle tree".into());
+        }
+        let root_history = match storage.root_history()? {
+            Some(history) => history,
+            None => {
+                let mut history = RootHistory::new(DEFAULT_ROOT_HISTORY_SIZE);
+                history.push(storage.inner(0)?);
+                history
+            }
+        };
+
+        Ok(MerkleTree {
+            height: tree_height(leaf_nodes_size),
+            storage,
+            leaf_hash_param: leaf_hash_param.clone(),
+            two_to_one_hash_param: two_to_one_hash_param.clone(),
+            root_history,
         })
     }
 
     /// Returns the root of the Merkle tree.
     pub fn root(&self) -> P::InnerDigest {
-        self.non_leaf_nodes[0].clone()
+        self.storage
+            .inner(0)
+            .expect("merkle tree storage should hold the root")
     }
 
     /// Returns the height of the Merkle tree.
@@ -332,19 +457,57 @@ impl<P: Config> MerkleTree<P> {
         self.height
     }
 
+    /// Returns the storage backend of the Merkle tree.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Make the nodes written so far durable in the storage backend.
+    pub fn flush(&mut self) -> Result<(), crate::Error> {
+        self.storage.flush()
+    }
+
+    /// Returns the most recent roots of the Merkle tree, the current root being the last one.
+    pub fn root_history(&self) -> &RootHistory<P> {
+        &self.root_history
+    }
+
+    /// Returns true iff `root` is the current root or one of the recent roots.
+    pub fn is_known_root(&self, root: &P::InnerDigest) -> bool {
+        self.root_history.contains(root)
+    }
+
+    /// Serialize the root history, see [`RootHistory::snapshot`].
+    pub fn snapshot_root_history(&self) -> Result<Vec<u8>, crate::Error> {
+        self.root_history.snapshot()
+    }
+
+    /// Replace the root history with a snapshot taken by [`MerkleTree::snapshot_root_history`].
+    ///
+    /// The snapshot must end with the current root of the tree.
+    pub fn restore_root_history(&mut self, snapshot: &[u8]) -> Result<(), crate::Error> {
+        let history = RootHistory::restore(snapshot)?;
+        if history.latest() != Some(&self.root()) {
+            return Err(Box::new(ark_serialize::SerializationError::InvalidData));
+        }
+        self.storage.set_root_history(&history)?;
+        self.root_history = history;
+        Ok(())
+    }
+
     /// Returns the authentication path from leaf at `index` to root.
     pub fn generate_proof(&self, index: usize) -> Result<Path<P>, crate::Error> {
         // gather basic tree information
-        let tree_height = tree_height(self.leaf_nodes.len());
+        let tree_height = tree_height(self.storage.num_leaves());
 
         // Get Leaf hash, and leaf sibling hash,
         let leaf_index_in_tree = convert_index_to_last_level(index, tree_height);
         let leaf_sibling_hash = if index & 1 == 0 {
             // leaf is left child
-            self.leaf_nodes[index + 1].clone()
+            self.storage.leaf(index + 1)?
         } else {
             // leaf is right child
-            self.leaf_nodes[index - 1].clone()
+            self.storage.leaf(index - 1)?
         };
 
         // path.len() = `tree height - 2`, the two missing elements being the leaf sibling hash and the root
@@ -353,7 +516,7 @@ impl<P: Config> MerkleTree<P> {
         let mut current_node = parent(leaf_index_in_tree).unwrap();
         while !is_root(current_node) {
             let sibling_node = sibling(current_node).unwrap();
-            path.push(self.non_leaf_nodes[sibling_node].clone());
+            path.push(self.storage.inner(sibling_node)?);
             current_node = parent(current_node).unwrap();
         }
 
@@ -382,9 +545,9 @@ impl<P: Config> MerkleTree<P> {
         // calculate leaf sibling hash and locate its position (left or right)
         let (leaf_left, leaf_right) = if index & 1 == 0 {
             // leaf on left
-            (&new_leaf_hash, &self.leaf_nodes[index + 1])
+            (new_leaf_hash.clone(), self.storage.leaf(index + 1)?)
         } else {
-            (&self.leaf_nodes[index - 1], &new_leaf_hash)
+            (self.storage.leaf(index - 1)?, new_leaf_hash.clone())
         };
 
         // calculate the updated hash at bottom non-leaf-level
@@ -392,8 +555,8 @@ impl<P: Config> MerkleTree<P> {
         {
             path_bottom_to_top.push(P::TwoToOneHash::evaluate(
                 &self.two_to_one_hash_param,
-                P::LeafInnerDigestConverter::convert(leaf_left.clone())?,
-                P::LeafInnerDigestConverter::convert(leaf_right.clone())?,
+                P::LeafInnerDigestConverter::convert(leaf_left)?,
+                P::LeafInnerDigestConverter::convert(leaf_right)?,
             )?);
         }
 
@@ -401,16 +564,11 @@ impl<P: Config> MerkleTree<P> {
         let leaf_index_in_tree = convert_index_to_last_level(index, self.height);
         let mut prev_index = parent(leaf_index_in_tree).unwrap();
         while !is_root(prev_index) {
+            let sibling_hash = self.storage.inner(sibling(prev_index).unwrap())?;
             let (left_child, right_child) = if is_left_child(prev_index) {
-                (
-                    path_bottom_to_top.last().unwrap(),
-                    &self.non_leaf_nodes[sibling(prev_index).unwrap()],
-                )
+                (path_bottom_to_top.last().unwrap(), &sibling_hash)
             } else {
-                (
-                    &self.non_leaf_nodes[sibling(prev_index).unwrap()],
-                    path_bottom_to_top.last().unwrap(),
-                )
+                (&sibling_hash, path_bottom_to_top.last().unwrap())
             };
             let evaluated =
                 P::TwoToOneHash::compress(&self.two_to_one_hash_param, left_child, right_child)?;
@@ -423,6 +581,24 @@ impl<P: Config> MerkleTree<P> {
         Ok((new_leaf_hash, path_top_to_bottom))
     }
 
+    /// Write an updated leaf and path returned by `updated_path`, and record the new root.
+    fn apply_updated_path(
+        &mut self,
+        index: usize,
+        updated_leaf_hash: P::LeafDigest,
+        mut updated_path: Vec<P::InnerDigest>,
+    ) -> Result<(), crate::Error> {
+        self.storage.set_leaf(index, updated_leaf_hash)?;
+        let mut curr_index = convert_index_to_last_level(index, self.height);
+        for _ in 0..self.height - 1 {
+            curr_index = parent(curr_index).unwrap();
+            self.storage
+                .set_inner(curr_index, updated_path.pop().unwrap())?;
+        }
+        self.root_history.push(self.root());
+        self.storage.set_root_history(&self.root_history)
+    }
+
     /// Update the leaf at `index` to updated leaf.
     /// ```tree_diagram
     ///         [A]
@@ -435,15 +611,9 @@ impl<P: Config> MerkleTree<P> {
     /// ```
     /// update(3, {new leaf}) would swap the leaf value at `[I]` and cause a recomputation of `[A]`, `[B]`, and `[E]`.
     pub fn update(&mut self, index: usize, new_leaf: &P::Leaf) -> Result<(), crate::Error> {
-        assert!(index < self.leaf_nodes.len(), "index out of range");
-        let (updated_leaf_hash, mut updated_path) = self.updated_path(index, new_leaf)?;
-        self.leaf_nodes[index] = updated_leaf_hash;
-        let mut curr_index = convert_index_to_last_level(index, self.height);
-        for _ in 0..self.height - 1 {
-            curr_index = parent(curr_index).unwrap();
-            self.non_leaf_nodes[curr_index] = updated_path.pop().unwrap();
-        }
-        Ok(())
+        assert!(index < self.storage.num_leaves(), "index out of range");
+        let (updated_leaf_hash, updated_path) = self.updated_path(index, new_leaf)?;
+        self.apply_updated_path(index, updated_leaf_hash, updated_path)
     }
 
     /// Update the leaf and check if the updated root is equal to `asserted_new_root`.
@@ -456,19 +626,153 @@ impl<P: Config> MerkleTree<P> {
         asserted_new_root: &P::InnerDigest,
     ) -> Result<bool, crate::Error> {
         let new_leaf = new_leaf.borrow();
-        assert!(index < self.leaf_nodes.len(), "index out of range");
-        let (updated_leaf_hash, mut updated_path) = self.updated_path(index, new_leaf)?;
+        assert!(index < self.storage.num_leaves(), "index out of range");
+        let (updated_leaf_hash, updated_path) = self.updated_path(index, new_leaf)?;
         if &updated_path[0] != asserted_new_root {
             return Ok(false);
         }
-        self.leaf_nodes[index] = updated_leaf_hash;
-        let mut curr_index = convert_index_to_last_level(index, self.height);
-        for _ in 0..self.height - 1 {
-            curr_index = parent(curr_index).unwrap();
-            self.non_leaf_nodes[curr_index] = updated_path.pop().unwrap();
-        }
+        self.apply_updated_path(index, updated_leaf_hash, updated_path)?;
         Ok(true)
     }
+
+    /// Update several leaves at once. Every inner node above an updated leaf is recomputed exactly once,
+    /// level by level. If an index appears more than once, the last leaf wins.
+    ///
+    /// The intermediate trees are never computed, so the batch pushes a single root onto the root
+    /// history, whereas updating the same leaves one by one pushes one root per update.
+    ///
+    /// With the `parallel` feature, the leaf hashes and the nodes of each level are computed with rayon.
+    pub fn update_batch<L>(&mut self, updates: &[(usize, L)]) -> Result<(), crate::Error>
+    where
+        L: Borrow<P::Leaf> + MaybeSync,
+        S: MaybeSync,
+        P::LeafDigest: MaybeSend,
+        P::InnerDigest: MaybeSend,
+        LeafParam<P>: MaybeSync,
+        TwoToOneParam<P>: MaybeSync,
+    {
+        if updates.is_empty() {
+            return Ok(());
+        }
+        let num_leaves = self.storage.num_leaves();
+
+        // keep the last leaf of every index
+        let mut new_leaves = BTreeMap::new();
+        for (index, leaf) in updates {
+            assert!(*index < num_leaves, "index out of range");
+            new_leaves.insert(*index, leaf);
+        }
+        let new_leaves: Vec<_> = new_leaves.into_iter().collect();
+
+        let leaf_hash_param = &self.leaf_hash_param;
+        let leaf_digests = try_map(&new_leaves, |(index, leaf)| {
+            let digest = P::LeafHash::evaluate(leaf_hash_param, (*leaf).borrow())?;
+            Ok((*index, digest))
+        })?;
+        for (index, digest) in leaf_digests {
+            self.storage.set_leaf(index, digest)?;
+        }
+
+        // recompute the parents of the updated nodes, from the bottom non-leaf level up to the root
+        let mut dirty: Vec<usize> = new_leaves
+            .iter()
+            .map(|(index, _)| parent(convert_index_to_last_level(*index, self.height)).unwrap())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        loop {
+            let nodes = {
+                let storage = &self.storage;
+                let two_to_one_hash_param = &self.two_to_one_hash_param;
+                try_map(&dirty, |&node| {
+                    let digest = Self::hash_children(storage, two_to_one_hash_param, node)?;
+                    Ok((node, digest))
+                })?
+            };
+            for (node, digest) in nodes {
+                self.storage.set_inner(node, digest)?;
+            }
+            if is_root(dirty[0]) {
+                break;
+            }
+            dirty = dirty
+                .iter()
+                .map(|node| parent(*node).unwrap())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+        }
+
+        self.root_history.push(self.root());
+        self.storage.set_root_history(&self.root_history)
+    }
+
+    /// Hash the children of the inner node `node`, which are leaves on the bottom non-leaf level.
+    fn hash_children(
+        storage: &S,
+        two_to_one_hash_param: &TwoToOneParam<P>,
+        node: usize,
+    ) -> Result<P::InnerDigest, crate::Error> {
+        let first_leaf = storage.num_leaves() - 1;
+        let (left, right) = (left_child(node), right_child(node));
+        if left >= first_leaf {
+            P::TwoToOneHash::evaluate(
+                two_to_one_hash_param,
+                P::LeafInnerDigestConverter::convert(storage.leaf(left - first_leaf)?)?,
+                P::LeafInnerDigestConverter::convert(storage.leaf(right - first_leaf)?)?,
+            )
+        } else {
+            P::TwoToOneHash::compress(
+                two_to_one_hash_param,
+                storage.inner(left)?,
+                storage.inner(right)?,
+            )
+        }
+    }
+}
+
+/// `Sync` with the `parallel` feature, implemented by every type otherwise.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync> MaybeSync for T {}
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSync for T {}
+
+/// `Send` with the `parallel` feature, implemented by every type otherwise.
+#[cfg(feature = "parallel")]
+pub trait MaybeSend: Send {}
+#[cfg(feature = "parallel")]
+impl<T: Send> MaybeSend for T {}
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSend {}
+#[cfg(not(feature = "parallel"))]
+impl<T> MaybeSend for T {}
+
+/// Apply `f` to every item, in parallel with the `parallel` feature.
+#[cfg(feature = "parallel")]
+fn try_map<T, U, F>(items: &[T], f: F) -> Result<Vec<U>, crate::Error>
+where
+    T: Sync,
+    U: Send,
+    F: Fn(&T) -> Result<U, crate::Error> + Sync + Send,
+{
+    // `crate::Error` is not `Send`, so errors leave the parallel iterator as strings
+    Ok(items
+        .par_iter()
+        .map(|item| f(item).map_err(|e| e.to_string()))
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Apply `f` to every item, in parallel with the `parallel` feature.
+#[cfg(not(feature = "parallel"))]
+fn try_map<T, U, F>(items: &[T], f: F) -> Result<Vec<U>, crate::Error>
+where
+    F: Fn(&T) -> Result<U, crate::Error>,
+{
+    items.iter().map(f).collect()
 }
 
 /// Returns the height of the tree, given the number of leaves.
@@ -530,3 +834,76 @@ fn parent(index: usize) -> Option<usize> {
 fn convert_index_to_last_level(index: usize, tree_height: usize) -> usize {
     index + (1 << (tree_height - 1)) - 1
 }
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_std::{rand::Rng, test_rng, UniformRand};
+
+    use crate::gadget::hashes::mimc7::{self, parameters};
+    use crate::gadget::merkle_tree::{
+        mimc::MiMCMerkleTreeConfig, Config, IdentityDigestConverter, MerkleTree,
+    };
+
+    type FieldMT = MerkleTree<MiMCMerkleTreeConfig<Fr>>;
+
+    /// Leaf and inner nodes share the same hash, so the tree must not be padded.
+    struct PlainMiMCConfig;
+
+    impl Config for PlainMiMCConfig {
+        type Leaf = [Fr];
+        type LeafDigest = Fr;
+        type LeafInnerDigestConverter = IdentityDigestConverter<Fr>;
+        type InnerDigest = Fr;
+
+        type LeafHash = mimc7::MiMC<Fr>;
+        type TwoToOneHash = mimc7::TwoToOneMiMC<Fr>;
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    fn test_padding_requires_domain_separation() {
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+        let leaves: Vec<Vec<Fr>> = (0..3).map(|i| vec![Fr::from(i as u64)]).collect();
+        let _ = MerkleTree::<PlainMiMCConfig>::new(&rc, &rc, leaves.iter().map(|l| l.as_slice()));
+    }
+
+    #[test]
+    fn test_update_batch() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        let leaves: Vec<Vec<Fr>> = (0..64).map(|_| vec![Fr::rand(rng)]).collect();
+        let mut sequential = FieldMT::new(&rc, &rc, leaves.iter().map(|l| l.as_slice())).unwrap();
+        let mut batched = sequential.clone();
+
+        for batch_size in [1, 2, 7, 40] {
+            // indices are random, so some of them repeat
+            let updates: Vec<(usize, Vec<Fr>)> = (0..batch_size)
+                .map(|_| (rng.gen_range(0..64), vec![Fr::rand(rng)]))
+                .collect();
+            for (index, leaf) in &updates {
+                sequential.update(*index, leaf).unwrap();
+            }
+            batched.update_batch(&updates).unwrap();
+
+            assert_eq!(sequential.root(), batched.root());
+            for i in 0..64 {
+                assert_eq!(
+                    sequential.generate_proof(i).unwrap().auth_path,
+                    batched.generate_proof(i).unwrap().auth_path
+                );
+            }
+            assert!(batched.is_known_root(&sequential.root()));
+        }
+
+        // an empty batch leaves the tree untouched
+        let root = batched.root();
+        batched.update_batch::<Vec<Fr>>(&[]).unwrap();
+        assert_eq!(root, batched.root());
+    }
+}
@@ -0,0 +1,682 @@
+use crate::gadget::merkle_tree::Config;
+use crate::Error;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, SerializationError};
+use ark_std::vec::Vec;
+use derivative::Derivative;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+/// Number of recent roots kept by a [`RootHistory`] unless specified otherwise.
+pub const DEFAULT_ROOT_HISTORY_SIZE: usize = 30;
+
+/// Backend holding the nodes of a [`MerkleTree`](super::MerkleTree).
+///
+/// Inner nodes are addressed in level order (the root is `0`, the children of `i` are `2*i+1`
+/// and `2*i+2`), leaves are addressed from left to right.
+pub trait MerkleTreeStorage<P: Config> {
+    /// Drop every stored node and make room for `num_leaves` leaves and `num_leaves - 1` inner nodes.
+    fn reset(&mut self, num_leaves: usize) -> Result<(), Error>;
+
+    /// Number of leaves the storage currently holds.
+    fn num_leaves(&self) -> usize;
+
+    fn leaf(&self, index: usize) -> Result<P::LeafDigest, Error>;
+
+    fn set_leaf(&mut self, index: usize, digest: P::LeafDigest) -> Result<(), Error>;
+
+    fn inner(&self, index: usize) -> Result<P::InnerDigest, Error>;
+
+    fn set_inner(&mut self, index: usize, digest: P::InnerDigest) -> Result<(), Error>;
+
+    /// The last root history written with `set_root_history`, if any.
+    fn root_history(&self) -> Result<Option<RootHistory<P>>, Error>;
+
+    fn set_root_history(&mut self, history: &RootHistory<P>) -> Result<(), Error>;
+
+    /// Make every previous write durable.
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// A bounded list of the most recent roots of a tree, oldest first.
+///
+/// Proofs generated against any root in the history can still be accepted after the tree moved on.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "P: Config"),
+    Debug(bound = "P: Config"),
+    PartialEq(bound = "P: Config")
+)]
+pub struct RootHistory<P: Config> {
+    capacity: usize,
+    roots: Vec<P::InnerDigest>,
+}
+
+impl<P: Config> RootHistory<P> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "root history capacity should be positive");
+        RootHistory {
+            capacity,
+            roots: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Record `root` as the current root, evicting the oldest one if the history is full.
+    pub fn push(&mut self, root: P::InnerDigest) {
+        if self.roots.last() == Some(&root) {
+            return;
+        }
+        if self.roots.len() == self.capacity {
+            self.roots.remove(0);
+        }
+        self.roots.push(root);
+    }
+
+    pub fn contains(&self, root: &P::InnerDigest) -> bool {
+        self.roots.contains(root)
+    }
+
+    /// Returns the most recent root.
+    pub fn latest(&self) -> Option<&P::InnerDigest> {
+        self.roots.last()
+    }
+
+    pub fn roots(&self) -> &[P::InnerDigest] {
+        &self.roots
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Serialize the history so it can be stored outside of the tree.
+    pub fn snapshot(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Rebuild a history from the output of [`RootHistory::snapshot`].
+    pub fn restore(mut bytes: &[u8]) -> Result<Self, Error> {
+        let history = Self::deserialize_compressed(&mut bytes)?;
+        if history.capacity == 0 || history.roots.len() > history.capacity {
+            return Err(Box::new(SerializationError::InvalidData));
+        }
+        Ok(history)
+    }
+}
+
+/// Keeps every node in memory. This is the default backend of [`MerkleTree`](super::MerkleTree).
+#[derive(Derivative)]
+#[derivative(Clone(bound = "P: Config"), Default(bound = "P: Config"))]
+pub struct MemoryStorage<P: Config> {
+    /// stores the non-leaf nodes in level order. The first element is the root node.
+    non_leaf_nodes: Vec<P::InnerDigest>,
+    /// store the hash of leaf nodes from left to right
+    leaf_nodes: Vec<P::LeafDigest>,
+    root_history: Option<RootHistory<P>>,
+}
+
+impl<P: Config> MerkleTreeStorage<P> for MemoryStorage<P> {
+    fn reset(&mut self, num_leaves: usize) -> Result<(), Error> {
+        self.leaf_nodes = vec![P::LeafDigest::default(); num_leaves];
+        self.non_leaf_nodes = vec![P::InnerDigest::default(); num_leaves.saturating_sub(1)];
+        self.root_history = None;
+        Ok(())
+    }
+
+    fn num_leaves(&self) -> usize {
+        self.leaf_nodes.len()
+    }
+
+    fn leaf(&self, index: usize) -> Result<P::LeafDigest, Error> {
+        Ok(self
+            .leaf_nodes
+            .get(index)
+            .ok_or("leaf index out of range")?
+            .clone())
+    }
+
+    fn set_leaf(&mut self, index: usize, digest: P::LeafDigest) -> Result<(), Error> {
+        *self
+            .leaf_nodes
+            .get_mut(index)
+            .ok_or("leaf index out of range")? = digest;
+        Ok(())
+    }
+
+    fn inner(&self, index: usize) -> Result<P::InnerDigest, Error> {
+        Ok(self
+            .non_leaf_nodes
+            .get(index)
+            .ok_or("inner node index out of range")?
+            .clone())
+    }
+
+    fn set_inner(&mut self, index: usize, digest: P::InnerDigest) -> Result<(), Error> {
+        *self
+            .non_leaf_nodes
+            .get_mut(index)
+            .ok_or("inner node index out of range")? = digest;
+        Ok(())
+    }
+
+    fn root_history(&self) -> Result<Option<RootHistory<P>>, Error> {
+        Ok(self.root_history.clone())
+    }
+
+    fn set_root_history(&mut self, history: &RootHistory<P>) -> Result<(), Error> {
+        self.root_history = Some(history.clone());
+        Ok(())
+    }
+}
+
+// record tags of the file storage log
+const RECORD_RESET: u8 = 0;
+const RECORD_LEAF: u8 = 1;
+const RECORD_INNER: u8 = 2;
+const RECORD_ROOT_HISTORY: u8 = 3;
+const RECORD_ROOT: u8 = 4;
+const RECORD_COMMIT: u8 = 5;
+
+/// A record of the file storage log, applied once the commit marker of its batch is read.
+enum Record<P: Config> {
+    Reset(usize),
+    Leaf(usize, P::LeafDigest),
+    Inner(usize, P::InnerDigest),
+    RootHistory(RootHistory<P>),
+    Root(P::InnerDigest),
+    Commit,
+}
+
+/// A leaf record holds at least a tag, an index and one byte of digest.
+const MIN_LEAF_RECORD_LEN: usize = 10;
+
+/// Persists the tree in an append-only log file.
+///
+/// Every write is appended to the log as a record serialized with `CanonicalSerialize`, and the
+/// nodes are mirrored in memory so reads never touch the disk. Records are buffered and written
+/// out together, followed by a commit marker, when the root history changes, i.e. once per tree
+/// update; a root pushed onto the history is logged on its own rather than with the whole history.
+///
+/// Opening an existing file replays the log one committed batch at a time. A batch torn by a crash
+/// at the end of the log, e.g. a reset whose leaves were only partly written, is dropped as a whole
+/// and the file is truncated after the last commit marker. Use [`FileStorage::compact`] to rewrite
+/// the log with only the current nodes.
+pub struct FileStorage<P: Config> {
+    path: PathBuf,
+    file: BufWriter<File>,
+    nodes: MemoryStorage<P>,
+}
+
+impl<P: Config> FileStorage<P> {
+    /// Open the log at `path`, creating it if it does not exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref().to_path_buf();
+        let mut nodes = MemoryStorage::default();
+        if path.exists() {
+            let bytes = fs::read(&path)?;
+            let complete_len = Self::replay(&mut nodes, &bytes)?;
+            if complete_len < bytes.len() {
+                let file = OpenOptions::new().write(true).open(&path)?;
+                file.set_len(complete_len as u64)?;
+                file.sync_all()?;
+            }
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(FileStorage {
+            path,
+            file: BufWriter::new(file),
+            nodes,
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Replays the committed batches of `log` and returns their length.
+    fn replay(nodes: &mut MemoryStorage<P>, log: &[u8]) -> Result<usize, Error> {
+        let mut bytes = log;
+        let mut batch = Vec::new();
+        let mut committed_len = 0;
+        loop {
+            match Self::read_record(&mut bytes) {
+                Ok(Some(Record::Commit)) => {
+                    for record in batch.drain(..) {
+                        Self::apply_record(nodes, record)?;
+                    }
+                    committed_len = log.len() - bytes.len();
+                }
+                Ok(Some(record)) => batch.push(record),
+                Ok(None) => return Ok(committed_len),
+                Err(SerializationError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => {
+                    return Ok(committed_len)
+                }
+                Err(e) => return Err(Box::new(e)),
+            }
+        }
+    }
+
+    /// Reads the record at the start of `bytes`. Returns `None` at the end of the log, which
+    /// includes a reset announcing more leaves than the rest of the log can hold.
+    fn read_record(bytes: &mut &[u8]) -> Result<Option<Record<P>>, SerializationError> {
+        if bytes.is_empty() {
+            return Ok(None);
+        }
+        let tag = u8::deserialize_compressed(&mut *bytes)?;
+        let record = match tag {
+            RECORD_RESET => {
+                let num_leaves = usize::deserialize_compressed(&mut *bytes)?;
+                if !num_leaves.is_power_of_two() || num_leaves < 2 {
+                    return Err(SerializationError::InvalidData);
+                }
+                // bounds the allocation by the size of the log
+                if num_leaves > bytes.len() / MIN_LEAF_RECORD_LEN {
+                    return Ok(None);
+                }
+                Record::Reset(num_leaves)
+            }
+            RECORD_LEAF => Record::Leaf(
+                usize::deserialize_compressed(&mut *bytes)?,
+                P::LeafDigest::deserialize_compressed(&mut *bytes)?,
+            ),
+            RECORD_INNER => Record::Inner(
+                usize::deserialize_compressed(&mut *bytes)?,
+                P::InnerDigest::deserialize_compressed(&mut *bytes)?,
+            ),
+            RECORD_ROOT_HISTORY => {
+                let history = RootHistory::deserialize_compressed(&mut *bytes)?;
+                if history.capacity == 0 || history.roots.len() > history.capacity {
+                    return Err(SerializationError::InvalidData);
+                }
+                Record::RootHistory(history)
+            }
+            RECORD_ROOT => Record::Root(P::InnerDigest::deserialize_compressed(&mut *bytes)?),
+            RECORD_COMMIT => Record::Commit,
+            _ => return Err(SerializationError::InvalidData),
+        };
+        Ok(Some(record))
+    }
+
+    /// Applies a record of a committed batch to `nodes`.
+    fn apply_record(
+        nodes: &mut MemoryStorage<P>,
+        record: Record<P>,
+    ) -> Result<(), SerializationError> {
+        match record {
+            Record::Reset(num_leaves) => {
+                nodes.leaf_nodes = vec![P::LeafDigest::default(); num_leaves];
+                nodes.non_leaf_nodes = vec![P::InnerDigest::default(); num_leaves - 1];
+                nodes.root_history = None;
+            }
+            Record::Leaf(index, digest) => {
+                *nodes
+                    .leaf_nodes
+                    .get_mut(index)
+                    .ok_or(SerializationError::InvalidData)? = digest;
+            }
+            Record::Inner(index, digest) => {
+                *nodes
+                    .non_leaf_nodes
+                    .get_mut(index)
+                    .ok_or(SerializationError::InvalidData)? = digest;
+            }
+            Record::RootHistory(history) => nodes.root_history = Some(history),
+            Record::Root(root) => nodes
+                .root_history
+                .as_mut()
+                .ok_or(SerializationError::InvalidData)?
+                .push(root),
+            Record::Commit => {}
+        }
+        Ok(())
+    }
+
+    fn append(&mut self, record: &[u8]) -> Result<(), Error> {
+        self.file.write_all(record)?;
+        Ok(())
+    }
+
+    /// Rewrite the log so it only contains the current nodes and root history.
+    pub fn compact(&mut self) -> Result<(), Error> {
+        self.file.flush()?;
+
+        let mut log = Vec::new();
+        RECORD_RESET.serialize_compressed(&mut log)?;
+        self.nodes.leaf_nodes.len().serialize_compressed(&mut log)?;
+        for (index, digest) in self.nodes.leaf_nodes.iter().enumerate() {
+            RECORD_LEAF.serialize_compressed(&mut log)?;
+            index.serialize_compressed(&mut log)?;
+            digest.serialize_compressed(&mut log)?;
+        }
+        for (index, digest) in self.nodes.non_leaf_nodes.iter().enumerate() {
+            RECORD_INNER.serialize_compressed(&mut log)?;
+            index.serialize_compressed(&mut log)?;
+            digest.serialize_compressed(&mut log)?;
+        }
+        if let Some(history) = &self.nodes.root_history {
+            RECORD_ROOT_HISTORY.serialize_compressed(&mut log)?;
+            history.serialize_compressed(&mut log)?;
+        }
+        RECORD_COMMIT.serialize_compressed(&mut log)?;
+
+        // write the new log next to the old one and swap them, so a crash leaves one of them intact
+        let tmp_path = self.path.with_extension("compact");
+        {
+            let mut tmp = File::create(&tmp_path)?;
+            tmp.write_all(&log)?;
+            tmp.sync_all()?;
+        }
+        fs::rename(&tmp_path, &self.path)?;
+        // make the rename itself durable
+        #[cfg(unix)]
+        if let Some(parent) = self.path.parent() {
+            let parent = if parent.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                parent
+            };
+            File::open(parent)?.sync_all()?;
+        }
+        self.file = BufWriter::new(OpenOptions::new().append(true).open(&self.path)?);
+        Ok(())
+    }
+}
+
+impl<P: Config> MerkleTreeStorage<P> for FileStorage<P> {
+    fn reset(&mut self, num_leaves: usize) -> Result<(), Error> {
+        let mut record = Vec::new();
+        RECORD_RESET.serialize_compressed(&mut record)?;
+        num_leaves.serialize_compressed(&mut record)?;
+        self.append(&record)?;
+        self.nodes.reset(num_leaves)
+    }
+
+    fn num_leaves(&self) -> usize {
+        self.nodes.num_leaves()
+    }
+
+    fn leaf(&self, index: usize) -> Result<P::LeafDigest, Error> {
+        self.nodes.leaf(index)
+    }
+
+    fn set_leaf(&mut self, index: usize, digest: P::LeafDigest) -> Result<(), Error> {
+        let mut record = Vec::new();
+        RECORD_LEAF.serialize_compressed(&mut record)?;
+        index.serialize_compressed(&mut record)?;
+        digest.serialize_compressed(&mut record)?;
+        self.append(&record)?;
+        self.nodes.set_leaf(index, digest)
+    }
+
+    fn inner(&self, index: usize) -> Result<P::InnerDigest, Error> {
+        self.nodes.inner(index)
+    }
+
+    fn set_inner(&mut self, index: usize, digest: P::InnerDigest) -> Result<(), Error> {
+        let mut record = Vec::new();
+        RECORD_INNER.serialize_compressed(&mut record)?;
+        index.serialize_compressed(&mut record)?;
+        digest.serialize_compressed(&mut record)?;
+        self.append(&record)?;
+        self.nodes.set_inner(index, digest)
+    }
+
+    fn root_history(&self) -> Result<Option<RootHistory<P>>, Error> {
+        self.nodes.root_history()
+    }
+
+    fn set_root_history(&mut self, history: &RootHistory<P>) -> Result<(), Error> {
+        // log only the new root if `history` extends the current one by a push
+        let pushed = self.nodes.root_history.as_ref().and_then(|current| {
+            let root = history.latest()?;
+            let mut extended = current.clone();
+            extended.push(root.clone());
+            (extended == *history).then_some(root)
+        });
+
+        let mut record = Vec::new();
+        match pushed {
+            Some(root) => {
+                RECORD_ROOT.serialize_compressed(&mut record)?;
+                root.serialize_compressed(&mut record)?;
+            }
+            None => {
+                RECORD_ROOT_HISTORY.serialize_compressed(&mut record)?;
+                history.serialize_compressed(&mut record)?;
+            }
+        }
+        // the root history closes a tree update, so it commits the batch
+        RECORD_COMMIT.serialize_compressed(&mut record)?;
+        self.append(&record)?;
+        self.file.flush()?;
+        self.nodes.set_root_history(history)
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    use crate::gadget::hashes::mimc7::{self, parameters};
+    use crate::gadget::merkle_tree::{mimc::MiMCMerkleTreeConfig, MerkleTree};
+
+    use super::{FileStorage, MemoryStorage, MerkleTreeStorage, RootHistory};
+
+    type FieldMTConfig = MiMCMerkleTreeConfig<Fr>;
+    type FieldMT<S> = MerkleTree<FieldMTConfig, S>;
+
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.log", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_file_storage_reopen() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+        let path = temp_path("merkle-file-storage");
+
+        let leaves: Vec<Vec<Fr>> = (0..8).map(|_| vec![Fr::rand(rng)]).collect();
+        let mut memory_tree =
+            MerkleTree::<FieldMTConfig>::new(&rc, &rc, leaves.iter().map(|l| l.as_slice()))
+                .unwrap();
+        let mut file_tree = FieldMT::new_with_storage(
+            FileStorage::open(&path).unwrap(),
+            &rc,
+            &rc,
+            leaves.iter().map(|l| l.as_slice()),
+        )
+        .unwrap();
+        assert_eq!(memory_tree.root(), file_tree.root());
+
+        let new_leaf = [Fr::rand(rng)];
+        memory_tree.update(3, &new_leaf).unwrap();
+        file_tree.update(3, &new_leaf).unwrap();
+        file_tree.flush().unwrap();
+        let old_root = file_tree.root_history().roots()[0];
+        drop(file_tree);
+
+        // the reopened tree has the same nodes and still knows the root before the update
+        let file_tree = FieldMT::open(FileStorage::open(&path).unwrap(), &rc, &rc).unwrap();
+        assert_eq!(memory_tree.root(), file_tree.root());
+        assert!(file_tree.is_known_root(&old_root));
+        assert!(file_tree.is_known_root(&file_tree.root()));
+        let proof = file_tree.generate_proof(3).unwrap();
+        assert!(proof
+            .verify(&rc, &rc, &file_tree.root(), new_leaf.as_slice())
+            .unwrap());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_storage_compact() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+        let path = temp_path("merkle-file-storage-compact");
+
+        let mut tree =
+            FieldMT::blank_with_storage(FileStorage::open(&path).unwrap(), &rc, &rc, 4).unwrap();
+        for i in 0..8 {
+            tree.update(i, &[Fr::rand(rng)]).unwrap();
+        }
+        let root = tree.root();
+        let log_len = std::fs::metadata(&path).unwrap().len();
+
+        let mut storage = FileStorage::<FieldMTConfig>::open(&path).unwrap();
+        storage.compact().unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() < log_len);
+
+        let tree = FieldMT::open(storage, &rc, &rc).unwrap();
+        assert_eq!(root, tree.root());
+        assert_eq!(tree.storage().num_leaves(), 8);
+        assert_eq!(tree.root_history().roots().len(), 9);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_storage_torn_tail() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+        let path = temp_path("merkle-file-storage-torn");
+
+        let mut tree =
+            FieldMT::blank_with_storage(FileStorage::open(&path).unwrap(), &rc, &rc, 3).unwrap();
+        tree.update(1, &[Fr::rand(rng)]).unwrap();
+        let root = tree.root();
+        let log_len = std::fs::metadata(&path).unwrap().len();
+
+        // each update only logs the updated path and the new root
+        tree.update(2, &[Fr::rand(rng)]).unwrap();
+        let update_len = std::fs::metadata(&path).unwrap().len() - log_len;
+        assert_eq!(update_len, 3 * (1 + 8 + 32) + (1 + 32) + 1);
+        drop(tree);
+
+        // a crash in the middle of the last update leaves a torn record behind
+        let log = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &log[..log_len as usize + 20]).unwrap();
+        let tree = FieldMT::open(FileStorage::open(&path).unwrap(), &rc, &rc).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), log_len);
+        assert_eq!(tree.root(), root);
+        assert!(tree.is_known_root(&root));
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_storage_torn_reset() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+        let path = temp_path("merkle-file-storage-torn-reset");
+
+        let tree =
+            FieldMT::blank_with_storage(FileStorage::open(&path).unwrap(), &rc, &rc, 3).unwrap();
+        let root = tree.root();
+        drop(tree);
+        let log_len = std::fs::metadata(&path).unwrap().len() as usize;
+
+        // rebuilding the tree resets the storage and logs all 16 leaves again
+        let leaves: Vec<Vec<Fr>> = (0..16).map(|_| vec![Fr::rand(rng)]).collect();
+        let tree = FieldMT::new_with_storage(
+            FileStorage::open(&path).unwrap(),
+            &rc,
+            &rc,
+            leaves.iter().map(|l| l.as_slice()),
+        )
+        .unwrap();
+        drop(tree);
+
+        // a crash after 10 of the 16 leaf records drops the whole reset
+        let log = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &log[..log_len + (1 + 8) + 10 * (1 + 8 + 32)]).unwrap();
+        let tree = FieldMT::open(FileStorage::open(&path).unwrap(), &rc, &rc).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len() as usize, log_len);
+        assert_eq!(tree.storage().num_leaves(), 4);
+        assert_eq!(tree.root(), root);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_file_storage_invalid_log() {
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+        let path = temp_path("merkle-file-storage-invalid");
+
+        // a reset announcing more leaves than the log holds is not replayed
+        let mut log = vec![0u8];
+        log.extend_from_slice(&(1u64 << 62).to_le_bytes());
+        log.extend_from_slice(&[0u8; 64]);
+        std::fs::write(&path, &log).unwrap();
+        let storage = FileStorage::<FieldMTConfig>::open(&path).unwrap();
+        assert_eq!(storage.num_leaves(), 0);
+        assert!(FieldMT::open(storage, &rc, &rc).is_err());
+
+        // an unknown record is rejected
+        std::fs::write(&path, [0xffu8; 16]).unwrap();
+        assert!(FileStorage::<FieldMTConfig>::open(&path).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_memory_storage_out_of_range() {
+        let mut storage = MemoryStorage::<FieldMTConfig>::default();
+        storage.reset(4).unwrap();
+        assert!(storage.leaf(3).is_ok());
+        assert!(storage.leaf(4).is_err());
+        assert!(storage.set_leaf(4, Fr::from(1u64)).is_err());
+        assert!(storage.inner(2).is_ok());
+        assert!(storage.inner(3).is_err());
+        assert!(storage.set_inner(3, Fr::from(1u64)).is_err());
+    }
+
+    #[test]
+    fn test_root_history_snapshot() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: parameters::get_bn256_round_constants(),
+        };
+
+        let mut history = RootHistory::<FieldMTConfig>::new(3);
+        let roots: Vec<Fr> = (0..4).map(|_| Fr::rand(rng)).collect();
+        for root in &roots {
+            history.push(*root);
+        }
+        assert!(!history.contains(&roots[0]));
+        assert_eq!(history.roots(), &roots[1..]);
+        let restored = RootHistory::<FieldMTConfig>::restore(&history.snapshot().unwrap()).unwrap();
+        assert_eq!(history, restored);
+
+        let mut tree = MerkleTree::<FieldMTConfig>::blank(&rc, &rc, 3).unwrap();
+        let snapshot = tree.snapshot_root_history().unwrap();
+        tree.update(0, &[Fr::rand(rng)]).unwrap();
+        // a snapshot that does not end with the current root is rejected
+        assert!(tree.restore_root_history(&snapshot).is_err());
+        let snapshot = tree.snapshot_root_history().unwrap();
+        tree.restore_root_history(&snapshot).unwrap();
+        assert_eq!(tree.root_history().roots().len(), 2);
+    }
+}
@@ -2,3 +2,7 @@ pub mod public_encryptions;
 pub mod symmetric_encrytions;
 pub mod hashes;
 pub mod merkle_tree;
+pub mod signatures;
+pub mod nullifier;
+pub mod commitments;
+pub mod range_check;
@@ -0,0 +1,23 @@
+use crate::gadget::hashes::{
+    constraints::CRHSchemeGadget,
+    mimc7::constraints::{MiMCGadget, ParametersVar},
+};
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, prelude::Boolean};
+use ark_relations::r1cs::SynthesisError;
+
+/// Circuit counterpart of [`super::nullifier`].
+pub fn nullifier<F: PrimeField + Absorb>(
+    rc: &ParametersVar<F>,
+    sk: &FpVar<F>,
+    nonce: &FpVar<F>,
+) -> Result<FpVar<F>, SynthesisError> {
+    MiMCGadget::<F>::evaluate(rc, &[sk.clone(), nonce.clone()])
+}
+
+/// Circuit counterpart of [`super::secret_key_to_field`] for a secret key given by its
+/// little-endian bits.
+pub fn secret_key_to_field<F: PrimeField>(sk: &[Boolean<F>]) -> Result<FpVar<F>, SynthesisError> {
+    Boolean::le_bits_to_fp_var(sk)
+}
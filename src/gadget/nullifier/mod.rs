@@ -0,0 +1,100 @@
+//! Nullifiers `nf = MiMC(sk || nonce)` marking one-time tokens as spent.
+//!
+//! The nullifier is deterministic in the token, so spending a token twice reveals the same `nf`,
+//! while it cannot be linked to the token without the secret key.
+
+pub mod constraints;
+pub mod store;
+
+pub use store::{MemoryNullifierStore, NullifierStore};
+
+use crate::gadget::hashes::{mimc7, CRHScheme};
+use crate::Error;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ff::{BigInteger, PrimeField};
+
+/// Returns the nullifier `MiMC(sk || nonce)`.
+pub fn nullifier<F: PrimeField + Absorb>(
+    rc: &mimc7::Parameters<F>,
+    sk: F,
+    nonce: F,
+) -> Result<F, Error> {
+    mimc7::MiMC::<F>::evaluate(rc, [sk, nonce].to_vec())
+}
+
+/// Embeds a secret scalar into the field `F`, which must be at least as large as the scalar field.
+pub fn secret_key_to_field<S: PrimeField, F: PrimeField>(sk: S) -> F {
+    F::from_le_bytes_mod_order(&sk.into_bigint().to_bytes_le())
+}
+
+/// The set of spent nullifiers, as kept by a verifier to reject replayed proofs.
+pub struct NullifierSet<F: PrimeField, S: NullifierStore<F> = MemoryNullifierStore<F>> {
+    store: S,
+    _field: ark_std::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField> NullifierSet<F> {
+    pub fn new() -> Self {
+        Self::with_store(MemoryNullifierStore::default())
+    }
+}
+
+impl<F: PrimeField> Default for NullifierSet<F> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<F: PrimeField, S: NullifierStore<F>> NullifierSet<F, S> {
+    pub fn with_store(store: S) -> Self {
+        NullifierSet {
+            store,
+            _field: ark_std::marker::PhantomData,
+        }
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    pub fn is_spent(&self, nf: &F) -> Result<bool, Error> {
+        self.store.contains(nf)
+    }
+
+    /// Marks `nf` as spent, failing if it already was.
+    pub fn spend(&mut self, nf: F) -> Result<(), Error> {
+        if !self.store.insert(nf)? {
+            return Err("nullifier already spent".into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::{nullifier, NullifierSet};
+    use crate::gadget::hashes::mimc7;
+
+    #[test]
+    fn test_nullifier_set_rejects_replays() {
+        let rng = &mut test_rng();
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+
+        let sk = Fr::rand(rng);
+        let nf = nullifier(&rc, sk, Fr::from(1u64)).unwrap();
+        let other_nf = nullifier(&rc, sk, Fr::from(2u64)).unwrap();
+        assert_ne!(nf, other_nf);
+
+        let mut set = NullifierSet::new();
+        assert!(!set.is_spent(&nf).unwrap());
+        set.spend(nf).unwrap();
+        assert!(set.is_spent(&nf).unwrap());
+        assert!(set.spend(nf).is_err());
+        set.spend(other_nf).unwrap();
+    }
+}
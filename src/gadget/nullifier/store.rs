@@ -0,0 +1,26 @@
+use crate::Error;
+use ark_ff::PrimeField;
+use std::collections::HashSet;
+
+/// Backend holding the spent nullifiers of a [`NullifierSet`](super::NullifierSet).
+pub trait NullifierStore<F: PrimeField> {
+    fn contains(&self, nf: &F) -> Result<bool, Error>;
+
+    /// Store `nf`, returning whether it was not stored before.
+    fn insert(&mut self, nf: F) -> Result<bool, Error>;
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MemoryNullifierStore<F: PrimeField> {
+    nullifiers: HashSet<F>,
+}
+
+impl<F: PrimeField> NullifierStore<F> for MemoryNullifierStore<F> {
+    fn contains(&self, nf: &F) -> Result<bool, Error> {
+        Ok(self.nullifiers.contains(nf))
+    }
+
+    fn insert(&mut self, nf: F) -> Result<bool, Error> {
+        Ok(self.nullifiers.insert(nf))
+    }
+}
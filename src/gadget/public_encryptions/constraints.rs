@@ -16,6 +16,7 @@ pub trait AsymmetricEncryptionGadget<C: AsymmetricEncryptionScheme, ConstraintF:
     type PlaintextVar: AllocVar<C::Plaintext, ConstraintF> + Clone;
     type PublicKeyVar: AllocVar<C::PublicKey, ConstraintF> + Clone;
     type RandomnessVar: AllocVar<C::Randomness, ConstraintF> + Clone;
+    type SecretKeyVar: AllocVar<C::SecretKey, ConstraintF> + Clone;
 
     fn encrypt(
         parameters: &Self::ParametersVar,
@@ -23,4 +24,10 @@ pub trait AsymmetricEncryptionGadget<C: AsymmetricEncryptionScheme, ConstraintF:
         randomness: &Self::RandomnessVar,
         public_key: &Self::PublicKeyVar,
     ) -> Result<Self::OutputVar, SynthesisError>;
+
+    fn decrypt(
+        parameters: &Self::ParametersVar,
+        ciphertext: &Self::OutputVar,
+        secret_key: &Self::SecretKeyVar,
+    ) -> Result<Self::PlaintextVar, SynthesisError>;
 }
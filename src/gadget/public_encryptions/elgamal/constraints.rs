@@ -3,21 +3,90 @@ use ark_relations::r1cs::{Namespace, SynthesisError};
 use derivative::Derivative;
 
 use crate::gadget::public_encryptions::elgamal::{
-    Ciphertext, ElGamal, Parameters, Plaintext, PublicKey, Randomness,
+    Ciphertext, ElGamal, MultiCiphertext, Parameters, Plaintext, PublicKey, Randomness, SecretKey,
 };
 use crate::gadget::public_encryptions::AsymmetricEncryptionGadget;
 use ark_ec::CurveGroup;
 use ark_ff::{
     fields::{Field, PrimeField},
-    Zero,
+    BigInteger, One,
 };
-use ark_serialize::CanonicalSerialize;
+use ark_r1cs_std::fields::fp::FpVar;
 use ark_std::{borrow::Borrow, marker::PhantomData, vec::Vec};
 
 pub type ConstraintF<C> = <<C as CurveGroup>::BaseField as Field>::BasePrimeField;
 
+/// Computes `bits*base` for little-endian `bits`. When `base` is a constant, the power-of-two
+/// multiples of it are precomputed natively, which is much cheaper than a variable-base
+/// double-and-add.
+pub fn scalar_mul_le<C, GG>(
+    base: &GG,
+    bits: &[Boolean<ConstraintF<C>>],
+) -> Result<GG, SynthesisError>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    if !base.is_constant() {
+        return base.scalar_mul_le(bits.iter());
+    }
+
+    let mut multiple = base.value()?;
+    let multiples = bits
+        .iter()
+        .map(|_| {
+            let current = multiple;
+            multiple.double_in_place();
+            current
+        })
+        .collect::<Vec<_>>();
+
+    let mut result = GG::zero();
+    result.precomputed_base_scalar_mul_le(bits.iter().zip(multiples.iter()))?;
+    Ok(result)
+}
+
+/// Allocates the little-endian bits of a scalar, one variable per bit of the scalar modulus.
+fn scalar_bits_le<C, F>(
+    cs: impl Into<Namespace<F>>,
+    scalar: Result<C::ScalarField, SynthesisError>,
+    mode: AllocationMode,
+) -> Result<Vec<Boolean<F>>, SynthesisError>
+where
+    C: CurveGroup,
+    F: PrimeField,
+{
+    let ns = cs.into();
+    let cs = ns.cs();
+    let num_bits = C::ScalarField::MODULUS_BIT_SIZE as usize;
+    let bits = scalar.map(|s| s.into_bigint().to_bits_le());
+    (0..num_bits)
+        .map(|i| {
+            Boolean::new_variable(
+                ark_relations::ns!(cs, "scalar_bit"),
+                || bits.as_ref().map(|b| b[i]).map_err(|e| *e),
+                mode,
+            )
+        })
+        .collect()
+}
+
+/// Randomness as little-endian scalar bits.
 #[derive(Clone, Debug)]
-pub struct RandomnessVar<F: Field>(pub Vec<UInt8<F>>);
+pub struct RandomnessVar<F: Field>(pub Vec<Boolean<F>>);
+
+impl<F: PrimeField> RandomnessVar<F> {
+    /// Derives the randomness from a field element, enforcing that it is smaller than the scalar
+    /// modulus of `C` so that it encodes exactly one scalar.
+    pub fn from_fpvar<C: CurveGroup>(r: &FpVar<F>) -> Result<Self, SynthesisError> {
+        let num_bits = C::ScalarField::MODULUS_BIT_SIZE as usize;
+        let mut bits = r.to_bits_le()?;
+        Boolean::enforce_smaller_or_equal_than_le(&bits, (-C::ScalarField::one()).into_bigint())?;
+        bits.truncate(num_bits);
+        Ok(Self(bits))
+    }
+}
 
 impl<C, F> AllocVar<Randomness<C>, F> for RandomnessVar<F>
 where
@@ -29,17 +98,36 @@ where
         f: impl FnOnce() -> Result<T, SynthesisError>,
         mode: AllocationMode,
     ) -> Result<Self, SynthesisError> {
-        let mut r = Vec::new();
-        let _ = &f()
-            .map(|b| b.borrow().0)
-            .unwrap_or(C::ScalarField::zero())
-            .serialize_compressed(&mut r)
-            .unwrap();
-        match mode {
-            AllocationMode::Constant => Ok(Self(UInt8::constant_vec(&r))),
-            AllocationMode::Input => UInt8::new_input_vec(cs, &r).map(Self),
-            AllocationMode::Witness => UInt8::new_witness_vec(cs, &r).map(Self),
-        }
+        scalar_bits_le::<C, F>(cs, f().map(|r| r.borrow().0), mode).map(Self)
+    }
+}
+
+/// Secret key as little-endian scalar bits.
+#[derive(Clone, Debug)]
+pub struct SecretKeyVar<F: Field>(pub Vec<Boolean<F>>);
+
+impl<F: PrimeField> SecretKeyVar<F> {
+    /// Wraps little-endian bits, enforcing that they encode a scalar smaller than the scalar
+    /// modulus of `C`. Otherwise `sk` and `sk + r` would both match the public key while anything
+    /// derived from the bits, such as a nullifier, would differ.
+    pub fn from_bits_le<C: CurveGroup>(bits: Vec<Boolean<F>>) -> Result<Self, SynthesisError> {
+        Boolean::enforce_smaller_or_equal_than_le(&bits, (-C::ScalarField::one()).into_bigint())?;
+        Ok(Self(bits))
+    }
+}
+
+impl<C, F> AllocVar<SecretKey<C>, F> for SecretKeyVar<F>
+where
+    C: CurveGroup,
+    F: PrimeField,
+{
+    fn new_variable<T: Borrow<SecretKey<C>>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let bits = scalar_bits_le::<C, F>(cs, f().map(|sk| sk.borrow().0), mode)?;
+        Self::from_bits_le::<C>(bits)
     }
 }
 
@@ -177,6 +265,95 @@ where
     }
 }
 
+#[derive(Derivative, Debug)]
+#[derivative(Clone(bound = "C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>"))]
+pub struct MultiOutputVar<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub c1: GG,
+    pub c2: Vec<GG>,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> MultiOutputVar<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    /// Allocates a ciphertext to `num_recipients` recipients. Unlike [`AllocVar::new_variable`],
+    /// the shape of the circuit does not depend on the assignment, so this also works in setup
+    /// mode.
+    pub fn new_variable_with_len<T: Borrow<MultiCiphertext<C>>>(
+        cs: impl Into<Namespace<ConstraintF<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        num_recipients: usize,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let prep = f().map(|c| c.borrow().clone());
+        if matches!(&prep, Ok(c) if c.c2.len() != num_recipients) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let c1 = GG::new_variable(
+            cs.clone(),
+            || prep.as_ref().map(|c| c.c1).map_err(|e| *e),
+            mode,
+        )?;
+        let c2 = (0..num_recipients)
+            .map(|i| {
+                GG::new_variable(
+                    cs.clone(),
+                    || prep.as_ref().map(|c| c.c2[i]).map_err(|e| *e),
+                    mode,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            c1,
+            c2,
+            _curve: PhantomData,
+        })
+    }
+}
+
+impl<C, GG> AllocVar<MultiCiphertext<C>, ConstraintF<C>> for MultiOutputVar<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    /// Allocates a ciphertext to as many recipients as the assignment, which is therefore
+    /// required even in setup mode. Use [`MultiOutputVar::new_variable_with_len`] when the number
+    /// of recipients is known.
+    fn new_variable<T: Borrow<MultiCiphertext<C>>>(
+        cs: impl Into<Namespace<ConstraintF<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ciphertext = f()?.borrow().clone();
+        let num_recipients = ciphertext.c2.len();
+        Self::new_variable_with_len(cs, || Ok(ciphertext), num_recipients, mode)
+    }
+}
+
+impl<C, GC> EqGadget<ConstraintF<C>> for MultiOutputVar<C, GC>
+where
+    C: CurveGroup,
+    GC: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GC: GroupOpsBounds<'a, C, GC>,
+{
+    #[inline]
+    fn is_eq(&self, other: &Self) -> Result<Boolean<ConstraintF<C>>, SynthesisError> {
+        if self.c2.len() != other.c2.len() {
+            return Ok(Boolean::FALSE);
+        }
+        self.c1.is_eq(&other.c1)?.and(&self.c2.is_eq(&other.c2)?)
+    }
+}
+
 pub struct ElGamalEncGadget<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
 where
     for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
@@ -186,6 +363,38 @@ where
     _group_var: PhantomData<*const GG>,
 }
 
+impl<C, GG> ElGamalEncGadget<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+    ConstraintF<C>: PrimeField,
+{
+    /// Encrypts the same message with the same randomness to every key of `public_keys`, the
+    /// circuit counterpart of [`ElGamal::encrypt_multi`].
+    pub fn encrypt_multi(
+        parameters: &ParametersVar<C, GG>,
+        message: &PlaintextVar<C, GG>,
+        randomness: &RandomnessVar<ConstraintF<C>>,
+        public_keys: &[PublicKeyVar<C, GG>],
+    ) -> Result<MultiOutputVar<C, GG>, SynthesisError> {
+        // compute c1 = randomness*generator
+        let c1 = scalar_mul_le(&parameters.generator, &randomness.0)?;
+
+        // compute c2[i] = m + randomness*pk[i]
+        let c2 = public_keys
+            .iter()
+            .map(|pk| Ok(message.plaintext.clone() + scalar_mul_le(&pk.pk, &randomness.0)?))
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
+
+        Ok(MultiOutputVar {
+            c1,
+            c2,
+            _curve: PhantomData,
+        })
+    }
+}
+
 impl<C, GG> AsymmetricEncryptionGadget<ElGamal<C>, ConstraintF<C>> for ElGamalEncGadget<C, GG>
 where
     C: CurveGroup,
@@ -198,6 +407,7 @@ where
     type PlaintextVar = PlaintextVar<C, GG>;
     type PublicKeyVar = PublicKeyVar<C, GG>;
     type RandomnessVar = RandomnessVar<ConstraintF<C>>;
+    type SecretKeyVar = SecretKeyVar<ConstraintF<C>>;
 
     fn encrypt(
         parameters: &Self::ParametersVar,
@@ -205,21 +415,11 @@ where
         randomness: &Self::RandomnessVar,
         public_key: &Self::PublicKeyVar,
     ) -> Result<Self::OutputVar, SynthesisError> {
-        // flatten randomness to little-endian bit vector
-        let randomness = randomness
-            .0
-            .iter()
-            .flat_map(|b| b.to_bits_le().unwrap())
-            .collect::<Vec<_>>();
-
         // compute s = randomness*pk
-        let s = public_key.pk.clone().scalar_mul_le(randomness.iter())?;
+        let s = scalar_mul_le(&public_key.pk, &randomness.0)?;
 
         // compute c1 = randomness*generator
-        let c1 = parameters
-            .generator
-            .clone()
-            .scalar_mul_le(randomness.iter())?;
+        let c1 = scalar_mul_le(&parameters.generator, &randomness.0)?;
 
         // compute c2 = m + s
         let c2 = message.plaintext.clone() + s;
@@ -230,6 +430,23 @@ where
             _curve: PhantomData,
         })
     }
+
+    fn decrypt(
+        _parameters: &Self::ParametersVar,
+        ciphertext: &Self::OutputVar,
+        secret_key: &Self::SecretKeyVar,
+    ) -> Result<Self::PlaintextVar, SynthesisError> {
+        // compute s = secret_key*c1
+        let s = ciphertext.c1.scalar_mul_le(secret_key.0.iter())?;
+
+        // compute m = c2 - s
+        let plaintext = ciphertext.c2.clone() - s;
+
+        Ok(Self::PlaintextVar {
+            plaintext,
+            _curve: PhantomData,
+        })
+    }
 }
 
 #[cfg(test)]
@@ -239,10 +456,18 @@ mod test {
 
     use ark_ed_on_bls12_381::{constraints::EdwardsVar, EdwardsProjective as JubJub, Fq};
 
-    use crate::gadget::public_encryptions::elgamal::{constraints::ElGamalEncGadget, ElGamal, Randomness};
+    use crate::gadget::public_encryptions::elgamal::{
+        constraints::{
+            ConstraintF, ElGamalEncGadget, MultiOutputVar, OutputVar, ParametersVar, PlaintextVar,
+            PublicKeyVar, RandomnessVar, SecretKeyVar,
+        },
+        ElGamal, MultiCiphertext, Randomness,
+    };
     use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
-    use ark_r1cs_std::prelude::*;
-    use ark_relations::r1cs::ConstraintSystem;
+    use ark_ec::CurveGroup;
+    use ark_ff::{BigInteger, PrimeField};
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisError, SynthesisMode};
 
     #[test]
     fn test_elgamal_gadget() {
@@ -302,4 +527,317 @@ mod test {
         assert_eq!(primitive_result.1, result_var.c2.value().unwrap());
         assert!(cs.is_satisfied().unwrap());
     }
+
+    fn test_elgamal_decrypt_gadget<C, GG>()
+    where
+        C: CurveGroup,
+        GG: CurveVar<C, ConstraintF<C>>,
+        for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+        ConstraintF<C>: PrimeField,
+    {
+        let rng = &mut test_rng();
+
+        // compute primitive result
+        let parameters = ElGamal::<C>::setup(rng).unwrap();
+        let (pk, sk) = ElGamal::<C>::keygen(&parameters, rng).unwrap();
+        let msg = C::rand(rng).into();
+        let randomness = Randomness::rand(rng);
+        let ciphertext = ElGamal::<C>::encrypt(&parameters, &pk, &msg, &randomness).unwrap();
+        let primitive_result = ElGamal::<C>::decrypt(&parameters, &sk, &ciphertext).unwrap();
+        assert_eq!(primitive_result, msg);
+
+        // construct constraint system
+        let cs = ConstraintSystem::<ConstraintF<C>>::new_ref();
+        let parameters_var = ParametersVar::<C, GG>::new_constant(
+            ark_relations::ns!(cs, "gadget_parameters"),
+            &parameters,
+        )
+        .unwrap();
+        let ciphertext_var =
+            OutputVar::<C, GG>::new_input(ark_relations::ns!(cs, "gadget_ciphertext"), || {
+                Ok(&ciphertext)
+            })
+            .unwrap();
+        let sk_var =
+            SecretKeyVar::new_witness(ark_relations::ns!(cs, "gadget_secret_key"), || Ok(&sk))
+                .unwrap();
+
+        // use gadget
+        let result_var =
+            ElGamalEncGadget::<C, GG>::decrypt(&parameters_var, &ciphertext_var, &sk_var).unwrap();
+
+        // check that result equals expected plaintext in the constraint system
+        let expected_var =
+            PlaintextVar::<C, GG>::new_input(ark_relations::ns!(cs, "gadget_expected"), || {
+                Ok(&primitive_result)
+            })
+            .unwrap();
+        expected_var
+            .plaintext
+            .enforce_equal(&result_var.plaintext)
+            .unwrap();
+
+        assert_eq!(
+            primitive_result,
+            result_var.plaintext.value().unwrap().into_affine()
+        );
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    fn encrypt_num_constraints(mode: AllocationMode) -> usize {
+        let rng = &mut test_rng();
+
+        type MyEnc = ElGamal<JubJub>;
+        type MyGadget = ElGamalEncGadget<JubJub, EdwardsVar>;
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, _) = MyEnc::keygen(&parameters, rng).unwrap();
+        let msg = JubJub::rand(rng).into();
+        let randomness = Randomness::rand(rng);
+        let primitive_result = MyEnc::encrypt(&parameters, &pk, &msg, &randomness).unwrap();
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let parameters_var = ParametersVar::<JubJub, EdwardsVar>::new_variable(
+            ark_relations::ns!(cs, "gadget_parameters"),
+            || Ok(&parameters),
+            mode,
+        )
+        .unwrap();
+        let randomness_var =
+            <MyGadget as AsymmetricEncryptionGadget<MyEnc, Fq>>::RandomnessVar::new_witness(
+                ark_relations::ns!(cs, "gadget_randomness"),
+                || Ok(&randomness),
+            )
+            .unwrap();
+        let msg_var = PlaintextVar::<JubJub, EdwardsVar>::new_witness(
+            ark_relations::ns!(cs, "gadget_message"),
+            || Ok(&msg),
+        )
+        .unwrap();
+        let pk_var =
+            <MyGadget as AsymmetricEncryptionGadget<MyEnc, Fq>>::PublicKeyVar::new_witness(
+                ark_relations::ns!(cs, "gadget_public_key"),
+                || Ok(&pk),
+            )
+            .unwrap();
+
+        let num_constraints = cs.num_constraints();
+        let result_var =
+            MyGadget::encrypt(&parameters_var, &msg_var, &randomness_var, &pk_var).unwrap();
+
+        assert_eq!(primitive_result.0, result_var.c1.value().unwrap());
+        assert_eq!(primitive_result.1, result_var.c2.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+
+        cs.num_constraints() - num_constraints
+    }
+
+    #[test]
+    fn test_elgamal_gadget_fixed_base() {
+        let fixed_base = encrypt_num_constraints(AllocationMode::Constant);
+        let variable_base = encrypt_num_constraints(AllocationMode::Witness);
+
+        // c1 = r*generator drops from a variable-base to a fixed-base multiplication
+        assert!(
+            fixed_base * 4 < variable_base * 3,
+            "fixed base: {fixed_base}, variable base: {variable_base}"
+        );
+    }
+
+    #[test]
+    fn test_elgamal_multi_recipient_gadget() {
+        let rng = &mut test_rng();
+
+        type MyEnc = ElGamal<JubJub>;
+        type MyGadget = ElGamalEncGadget<JubJub, EdwardsVar>;
+
+        // compute primitive result
+        let parameters = MyEnc::setup(rng).unwrap();
+        let pks = (0..3)
+            .map(|_| MyEnc::keygen(&parameters, rng).unwrap().0)
+            .collect::<Vec<_>>();
+        let msg = JubJub::rand(rng).into();
+        let randomness = Randomness::rand(rng);
+        let primitive_result = MyEnc::encrypt_multi(&parameters, &pks, &msg, &randomness).unwrap();
+
+        // a different message to the last recipient
+        let mut mixed_result = primitive_result.clone();
+        let other_msg = JubJub::rand(rng).into();
+        mixed_result.c2[2] = MyEnc::encrypt(&parameters, &pks[2], &other_msg, &randomness)
+            .unwrap()
+            .1;
+
+        for (expected, satisfied) in [(primitive_result, true), (mixed_result, false)] {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let parameters_var =
+                ParametersVar::new_constant(ark_relations::ns!(cs, "parameters"), &parameters)
+                    .unwrap();
+            let randomness_var =
+                RandomnessVar::new_witness(ark_relations::ns!(cs, "randomness"), || {
+                    Ok(&randomness)
+                })
+                .unwrap();
+            let msg_var =
+                PlaintextVar::new_witness(ark_relations::ns!(cs, "message"), || Ok(&msg)).unwrap();
+            let pk_vars = pks
+                .iter()
+                .map(|pk| {
+                    PublicKeyVar::new_input(ark_relations::ns!(cs, "public_key"), || Ok(pk))
+                        .unwrap()
+                })
+                .collect::<Vec<_>>();
+
+            let result_var =
+                MyGadget::encrypt_multi(&parameters_var, &msg_var, &randomness_var, &pk_vars)
+                    .unwrap();
+
+            let expected_var =
+                MultiOutputVar::new_input(ark_relations::ns!(cs, "expected"), || Ok(&expected))
+                    .unwrap();
+            expected_var.enforce_equal(&result_var).unwrap();
+
+            assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+        }
+    }
+
+    #[test]
+    fn test_randomness_var_missing_assignment() {
+        let missing = || Err::<Randomness<JubJub>, _>(SynthesisError::AssignmentMissing);
+
+        // proving without randomness fails instead of encrypting with zero
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let result = RandomnessVar::new_witness(ark_relations::ns!(cs, "randomness"), missing);
+        assert_eq!(result.unwrap_err(), SynthesisError::AssignmentMissing);
+
+        // setup does not need an assignment
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let randomness_var =
+            RandomnessVar::new_witness(ark_relations::ns!(cs, "randomness"), missing).unwrap();
+        assert_eq!(
+            randomness_var.0.len(),
+            <JubJub as ark_ec::Group>::ScalarField::MODULUS_BIT_SIZE as usize
+        );
+    }
+
+    #[test]
+    fn test_multi_output_var_missing_assignment() {
+        let missing = || Err::<MultiCiphertext<JubJub>, _>(SynthesisError::AssignmentMissing);
+
+        // setup allocates one ciphertext component per recipient without an assignment
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let output_var = MultiOutputVar::<JubJub, EdwardsVar>::new_variable_with_len(
+            ark_relations::ns!(cs, "ciphertext"),
+            missing,
+            3,
+            AllocationMode::Input,
+        )
+        .unwrap();
+        assert_eq!(output_var.c2.len(), 3);
+        assert_eq!(cs.num_instance_variables(), 1 + 2 * 4);
+
+        // proving without an assignment fails
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let result = MultiOutputVar::<JubJub, EdwardsVar>::new_variable_with_len(
+            ark_relations::ns!(cs, "ciphertext"),
+            missing,
+            3,
+            AllocationMode::Input,
+        );
+        assert_eq!(result.unwrap_err(), SynthesisError::AssignmentMissing);
+    }
+
+    #[test]
+    fn test_secret_key_var_rejects_unreduced_bits() {
+        let rng = &mut test_rng();
+        type ScalarField = <JubJub as ark_ec::Group>::ScalarField;
+
+        let parameters = ElGamal::<JubJub>::setup(rng).unwrap();
+        let sk = ScalarField::from(u64::rand(rng));
+        let pk: JubJub = parameters.generator * sk;
+
+        // `sk + r` has the same public key but other bits
+        let mut unreduced = sk.into_bigint();
+        unreduced.add_with_carry(&ScalarField::MODULUS);
+        let num_bits = ScalarField::MODULUS_BIT_SIZE as usize;
+
+        for (bits, satisfied) in [
+            (sk.into_bigint().to_bits_le(), true),
+            (unreduced.to_bits_le(), false),
+        ] {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let bits = bits[..num_bits]
+                .iter()
+                .map(|b| Boolean::new_witness(ark_relations::ns!(cs, "bit"), || Ok(*b)).unwrap())
+                .collect::<Vec<_>>();
+            let sk_var = SecretKeyVar::from_bits_le::<JubJub>(bits).unwrap();
+
+            let generator =
+                EdwardsVar::new_constant(ark_relations::ns!(cs, "generator"), parameters.generator)
+                    .unwrap();
+            let pk_var = generator.scalar_mul_le(sk_var.0.iter()).unwrap();
+            assert_eq!(pk_var.value().unwrap(), pk);
+            assert_eq!(cs.is_satisfied().unwrap(), satisfied);
+        }
+    }
+
+    #[test]
+    fn test_randomness_var_from_fpvar() {
+        let rng = &mut test_rng();
+
+        type MyEnc = ElGamal<JubJub>;
+        type MyGadget = ElGamalEncGadget<JubJub, EdwardsVar>;
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, _) = MyEnc::keygen(&parameters, rng).unwrap();
+        let msg = JubJub::rand(rng).into();
+        let randomness = Randomness::<JubJub>::rand(rng);
+        let primitive_result = MyEnc::encrypt(&parameters, &pk, &msg, &randomness).unwrap();
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let parameters_var =
+            ParametersVar::new_constant(ark_relations::ns!(cs, "parameters"), &parameters).unwrap();
+        let msg_var =
+            PlaintextVar::new_witness(ark_relations::ns!(cs, "message"), || Ok(&msg)).unwrap();
+        let pk_var =
+            <MyGadget as AsymmetricEncryptionGadget<MyEnc, Fq>>::PublicKeyVar::new_witness(
+                ark_relations::ns!(cs, "public_key"),
+                || Ok(&pk),
+            )
+            .unwrap();
+        let r = FpVar::new_witness(ark_relations::ns!(cs, "r"), || {
+            Ok(Fq::from_bigint(randomness.0.into_bigint()).unwrap())
+        })
+        .unwrap();
+        let randomness_var = RandomnessVar::from_fpvar::<JubJub>(&r).unwrap();
+
+        let result_var =
+            MyGadget::encrypt(&parameters_var, &msg_var, &randomness_var, &pk_var).unwrap();
+        assert_eq!(primitive_result.0, result_var.c1.value().unwrap());
+        assert_eq!(primitive_result.1, result_var.c2.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+
+        // the scalar modulus itself is out of range
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let r = FpVar::new_witness(ark_relations::ns!(cs, "r"), || {
+            Ok(Fq::from_bigint(<JubJub as ark_ec::Group>::ScalarField::MODULUS).unwrap())
+        })
+        .unwrap();
+        RandomnessVar::from_fpvar::<JubJub>(&r).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_elgamal_decrypt_gadget_jubjub() {
+        test_elgamal_decrypt_gadget::<JubJub, EdwardsVar>();
+    }
+
+    #[test]
+    fn test_elgamal_decrypt_gadget_ed_on_bn254() {
+        test_elgamal_decrypt_gadget::<
+            ark_ed_on_bn254::EdwardsProjective,
+            ark_ed_on_bn254::constraints::EdwardsVar,
+        >();
+    }
 }
@@ -4,22 +4,52 @@ use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
 use crate::Error;
 use ark_ec::{CurveGroup, Group};
 use ark_ff::{fields::PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::marker::PhantomData;
 use ark_std::ops::Mul;
 use ark_std::rand::Rng;
+use derivative::Derivative;
 
 pub struct ElGamal<C: CurveGroup> {
     _group: PhantomData<C>,
 }
 
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
 pub struct Parameters<C: CurveGroup> {
     pub generator: C::Affine,
 }
 
+impl<C: CurveGroup> Parameters<C> {
+    /// Parameters using the curve's standard generator, so that independently set up parties
+    /// share them without coordination.
+    pub fn standard() -> Self {
+        Self {
+            generator: C::generator().into(),
+        }
+    }
+}
+
 pub type PublicKey<C> = <C as CurveGroup>::Affine;
 
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
 pub struct SecretKey<C: CurveGroup>(pub C::ScalarField);
 
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
 pub struct Randomness<C: CurveGroup>(pub C::ScalarField);
 
 impl<C: CurveGroup> UniformRand for Randomness<C> {
@@ -33,6 +63,67 @@ pub type Plaintext<C> = <C as CurveGroup>::Affine;
 
 pub type Ciphertext<C> = (<C as CurveGroup>::Affine, <C as CurveGroup>::Affine);
 
+/// One message encrypted to several recipients, sharing the ephemeral `c1 = r*generator`.
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
+pub struct MultiCiphertext<C: CurveGroup> {
+    pub c1: C::Affine,
+    /// `m + r*pk[i]` for the i-th recipient
+    pub c2: Vec<C::Affine>,
+}
+
+impl<C: CurveGroup> MultiCiphertext<C> {
+    /// Returns the ciphertext of the `index`-th recipient.
+    pub fn ciphertext(&self, index: usize) -> Option<Ciphertext<C>> {
+        self.c2.get(index).map(|c2| (self.c1, *c2))
+    }
+}
+
+impl<C: CurveGroup> ElGamal<C>
+where
+    C::ScalarField: PrimeField,
+{
+    /// Encrypts `message` to every key of `pks` with the same randomness. Each recipient decrypts
+    /// its [`MultiCiphertext::ciphertext`] with [`AsymmetricEncryptionScheme::decrypt`].
+    pub fn encrypt_multi(
+        pp: &Parameters<C>,
+        pks: &[PublicKey<C>],
+        message: &Plaintext<C>,
+        r: &Randomness<C>,
+    ) -> Result<MultiCiphertext<C>, Error> {
+        // compute c1 = r*generator
+        let c1 = pp.generator.mul(r.0).into_affine();
+
+        // compute c2[i] = m + r*pk[i]
+        let c2 = pks
+            .iter()
+            .map(|pk| *message + pk.mul(r.0))
+            .collect::<Vec<_>>();
+
+        Ok(MultiCiphertext {
+            c1,
+            c2: C::normalize_batch(&c2),
+        })
+    }
+
+    /// Decrypts the `index`-th recipient's part of a [`MultiCiphertext`].
+    pub fn decrypt_multi(
+        pp: &Parameters<C>,
+        sk: &SecretKey<C>,
+        ciphertext: &MultiCiphertext<C>,
+        index: usize,
+    ) -> Result<Plaintext<C>, Error> {
+        let ciphertext = ciphertext
+            .ciphertext(index)
+            .ok_or("recipient index out of range")?;
+        Self::decrypt(pp, sk, &ciphertext)
+    }
+}
+
 impl<C: CurveGroup> AsymmetricEncryptionScheme for ElGamal<C>
 where
     C::ScalarField: PrimeField,
@@ -44,11 +135,8 @@ where
     type Plaintext = Plaintext<C>;
     type Ciphertext = Ciphertext<C>;
 
-    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error> {
-        // get a random generator
-        let generator = C::rand(rng).into();
-
-        Ok(Parameters { generator })
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(Parameters::standard())
     }
 
     fn keygen<R: Rng>(
@@ -103,11 +191,14 @@ where
 
 #[cfg(test)]
 mod test {
-    use ark_std::{test_rng, UniformRand};
+    use ark_std::{
+        rand::{rngs::StdRng, SeedableRng},
+        test_rng, UniformRand,
+    };
 
     use ark_ed_on_bls12_381::EdwardsProjective as JubJub;
 
-    use crate::gadget::public_encryptions::elgamal::{ElGamal, Randomness};
+    use crate::gadget::public_encryptions::elgamal::{ElGamal, Parameters, Randomness};
     use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
 
     #[test]
@@ -128,4 +219,38 @@ mod test {
 
         assert_eq!(msg, check_msg);
     }
+
+    #[test]
+    fn test_elgamal_multi_recipient() {
+        let rng = &mut test_rng();
+
+        let parameters = ElGamal::<JubJub>::setup(rng).unwrap();
+        let keys = (0..3)
+            .map(|_| ElGamal::<JubJub>::keygen(&parameters, rng).unwrap())
+            .collect::<Vec<_>>();
+        let pks = keys.iter().map(|(pk, _)| *pk).collect::<Vec<_>>();
+
+        let msg = JubJub::rand(rng).into();
+        let r = Randomness::rand(rng);
+        let cipher = ElGamal::<JubJub>::encrypt_multi(&parameters, &pks, &msg, &r).unwrap();
+
+        for (i, (pk, sk)) in keys.iter().enumerate() {
+            let check_msg = ElGamal::<JubJub>::decrypt_multi(&parameters, sk, &cipher, i).unwrap();
+            assert_eq!(msg, check_msg);
+
+            // each part is an ordinary ciphertext
+            let single = ElGamal::<JubJub>::encrypt(&parameters, pk, &msg, &r).unwrap();
+            assert_eq!(cipher.ciphertext(i).unwrap(), single);
+        }
+        assert!(ElGamal::<JubJub>::decrypt_multi(&parameters, &keys[0].1, &cipher, 3).is_err());
+    }
+
+    #[test]
+    fn test_elgamal_setup_is_deterministic() {
+        let parameters = ElGamal::<JubJub>::setup(&mut test_rng()).unwrap();
+        let other_parameters = ElGamal::<JubJub>::setup(&mut StdRng::seed_from_u64(1)).unwrap();
+
+        assert_eq!(parameters, other_parameters);
+        assert_eq!(parameters, Parameters::standard());
+    }
 }
@@ -0,0 +1,347 @@
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use derivative::Derivative;
+
+use crate::gadget::hashes::{
+    constraints::CRHSchemeGadget,
+    mimc7::{self, constraints::MiMCGadget},
+};
+use crate::gadget::public_encryptions::elgamal::{
+    constraints::{
+        scalar_mul_le, ConstraintF, ParametersVar, PublicKeyVar, RandomnessVar, SecretKeyVar,
+    },
+    Parameters,
+};
+use crate::gadget::public_encryptions::exp_elgamal::{
+    Ciphertext, ExpElGamal, Plaintext, MAX_DECRYPT_BITS,
+};
+use crate::gadget::public_encryptions::AsymmetricEncryptionGadget;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::CurveGroup;
+use ark_ff::fields::{Field, PrimeField};
+use ark_std::{borrow::Borrow, marker::PhantomData, vec::Vec};
+
+/// Plaintext as little-endian bits; the number of bits bounds the value.
+#[derive(Clone, Debug)]
+pub struct PlaintextVar<F: Field>(pub Vec<Boolean<F>>);
+
+impl<F: PrimeField> PlaintextVar<F> {
+    /// Returns the plaintext as a field element.
+    pub fn to_fpvar(&self) -> Result<FpVar<F>, SynthesisError> {
+        Boolean::le_bits_to_fp_var(&self.0)
+    }
+}
+
+impl<F: PrimeField> AllocVar<Plaintext, F> for PlaintextVar<F> {
+    fn new_variable<T: Borrow<Plaintext>>(
+        cs: impl Into<Namespace<F>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let m = f().map(|m| *m.borrow());
+        (0..Plaintext::BITS)
+            .map(|i| {
+                Boolean::new_variable(
+                    ark_relations::ns!(cs, "plaintext_bit"),
+                    || m.map(|m| (m >> i) & 1 == 1),
+                    mode,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(Self)
+    }
+}
+
+#[derive(Derivative, Debug)]
+#[derivative(Clone(bound = "C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>"))]
+pub struct OutputVar<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub c1: GG,
+    pub c2: GG,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> AllocVar<Ciphertext<C>, ConstraintF<C>> for OutputVar<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn new_variable<T: Borrow<Ciphertext<C>>>(
+        cs: impl Into<Namespace<ConstraintF<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let prep = f().map(|c| *c.borrow());
+        let c1 = GG::new_variable(cs.clone(), || prep.map(|c| c.c1), mode)?;
+        let c2 = GG::new_variable(cs.clone(), || prep.map(|c| c.c2), mode)?;
+        Ok(Self {
+            c1,
+            c2,
+            _curve: PhantomData,
+        })
+    }
+}
+
+impl<C, GC> EqGadget<ConstraintF<C>> for OutputVar<C, GC>
+where
+    C: CurveGroup,
+    GC: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GC: GroupOpsBounds<'a, C, GC>,
+{
+    #[inline]
+    fn is_eq(&self, other: &Self) -> Result<Boolean<ConstraintF<C>>, SynthesisError> {
+        self.c1.is_eq(&other.c1)?.and(&self.c2.is_eq(&other.c2)?)
+    }
+}
+
+pub struct ExpElGamalEncGadget<C: CurveGroup, GG: CurveVar<C, ConstraintF<C>>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    #[doc(hidden)]
+    _curve: PhantomData<*const C>,
+    _group_var: PhantomData<*const GG>,
+}
+
+impl<C, GG> ExpElGamalEncGadget<C, GG>
+where
+    C: CurveGroup,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+    ConstraintF<C>: PrimeField + Absorb,
+{
+    /// Encrypts `value`, enforcing that it is smaller than `2^num_bits` and that
+    /// `commitment == MiMC(value || opening)`, see [`super::commit`].
+    #[allow(clippy::too_many_arguments)]
+    pub fn encrypt_committed(
+        parameters: &ParametersVar<C, GG>,
+        hash_parameters: &mimc7::constraints::ParametersVar<ConstraintF<C>>,
+        value: &FpVar<ConstraintF<C>>,
+        num_bits: usize,
+        opening: &FpVar<ConstraintF<C>>,
+        commitment: &FpVar<ConstraintF<C>>,
+        randomness: &RandomnessVar<ConstraintF<C>>,
+        public_key: &PublicKeyVar<C, GG>,
+    ) -> Result<OutputVar<C, GG>, SynthesisError> {
+        // commitment == MiMC(value || opening)
+        MiMCGadget::<ConstraintF<C>>::evaluate(hash_parameters, &[value.clone(), opening.clone()])?
+            .enforce_equal(commitment)?;
+
+        // value < 2^num_bits
+        let mut bits = value.to_bits_le()?;
+        for bit in bits.iter().skip(num_bits) {
+            bit.enforce_equal(&Boolean::FALSE)?;
+        }
+        bits.truncate(num_bits);
+
+        Self::encrypt(parameters, &PlaintextVar(bits), randomness, public_key)
+    }
+}
+
+impl<C, GG> AsymmetricEncryptionGadget<ExpElGamal<C>, ConstraintF<C>> for ExpElGamalEncGadget<C, GG>
+where
+    C: CurveGroup,
+    C::ScalarField: PrimeField,
+    GG: CurveVar<C, ConstraintF<C>>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+    ConstraintF<C>: PrimeField,
+{
+    type OutputVar = OutputVar<C, GG>;
+    type ParametersVar = ParametersVar<C, GG>;
+    type PlaintextVar = PlaintextVar<ConstraintF<C>>;
+    type PublicKeyVar = PublicKeyVar<C, GG>;
+    type RandomnessVar = RandomnessVar<ConstraintF<C>>;
+    type SecretKeyVar = SecretKeyVar<ConstraintF<C>>;
+
+    fn encrypt(
+        parameters: &Self::ParametersVar,
+        message: &Self::PlaintextVar,
+        randomness: &Self::RandomnessVar,
+        public_key: &Self::PublicKeyVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        // compute s = randomness*pk
+        let s = scalar_mul_le(&public_key.pk, &randomness.0)?;
+
+        // compute c1 = randomness*generator
+        let c1 = scalar_mul_le(&parameters.generator, &randomness.0)?;
+
+        // compute c2 = m*generator + s
+        let c2 = scalar_mul_le(&parameters.generator, &message.0)? + s;
+
+        Ok(Self::OutputVar {
+            c1,
+            c2,
+            _curve: PhantomData,
+        })
+    }
+
+    /// Decrypts plaintexts smaller than `2^MAX_DECRYPT_BITS`; larger plaintexts are unsatisfiable.
+    fn decrypt(
+        parameters: &Self::ParametersVar,
+        ciphertext: &Self::OutputVar,
+        secret_key: &Self::SecretKeyVar,
+    ) -> Result<Self::PlaintextVar, SynthesisError> {
+        // compute m*generator = c2 - secret_key*c1
+        let target = ciphertext.c2.clone() - ciphertext.c1.scalar_mul_le(secret_key.0.iter())?;
+
+        // witness m natively and check it against the target
+        let cs = target.cs();
+        let m = || -> Result<Plaintext, SynthesisError> {
+            let pp = Parameters {
+                generator: parameters.generator.value()?.into_affine(),
+            };
+            Ok(ExpElGamal::<C>::discrete_log(&pp, target.value()?, MAX_DECRYPT_BITS).unwrap_or(0))
+        };
+        let m = m();
+        let bits = (0..MAX_DECRYPT_BITS)
+            .map(|i| {
+                Boolean::new_witness(ark_relations::ns!(cs, "plaintext_bit"), || {
+                    m.map(|m| (m >> i) & 1 == 1)
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        scalar_mul_le(&parameters.generator, &bits)?.enforce_equal(&target)?;
+
+        Ok(PlaintextVar(bits))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_std::{test_rng, UniformRand};
+
+    use ark_ed_on_bn254::{constraints::EdwardsVar, EdwardsProjective, Fq};
+
+    use crate::gadget::hashes::mimc7;
+    use crate::gadget::public_encryptions::constraints::AsymmetricEncryptionGadget;
+    use crate::gadget::public_encryptions::elgamal::{
+        constraints::{ParametersVar, PublicKeyVar, RandomnessVar, SecretKeyVar},
+        Randomness,
+    };
+    use crate::gadget::public_encryptions::exp_elgamal::{
+        commit,
+        constraints::{ExpElGamalEncGadget, OutputVar, PlaintextVar},
+        ExpElGamal,
+    };
+    use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    type MyEnc = ExpElGamal<EdwardsProjective>;
+    type MyGadget = ExpElGamalEncGadget<EdwardsProjective, EdwardsVar>;
+
+    #[test]
+    fn test_exp_elgamal_gadget() {
+        let rng = &mut test_rng();
+
+        // compute primitive result
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+        let msg = 123_456u64;
+        let randomness = Randomness::rand(rng);
+        let primitive_result = MyEnc::encrypt(&parameters, &pk, &msg, &randomness).unwrap();
+
+        // construct constraint system
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let parameters_var =
+            ParametersVar::new_constant(ark_relations::ns!(cs, "parameters"), &parameters).unwrap();
+        let msg_var =
+            PlaintextVar::new_witness(ark_relations::ns!(cs, "message"), || Ok(msg)).unwrap();
+        let randomness_var =
+            RandomnessVar::new_witness(ark_relations::ns!(cs, "randomness"), || Ok(&randomness))
+                .unwrap();
+        let pk_var =
+            PublicKeyVar::new_witness(ark_relations::ns!(cs, "public_key"), || Ok(&pk)).unwrap();
+        let sk_var =
+            SecretKeyVar::new_witness(ark_relations::ns!(cs, "secret_key"), || Ok(&sk)).unwrap();
+
+        // use gadget
+        let result_var =
+            MyGadget::encrypt(&parameters_var, &msg_var, &randomness_var, &pk_var).unwrap();
+        let decrypted_var = MyGadget::decrypt(&parameters_var, &result_var, &sk_var).unwrap();
+
+        // check that result equals expected ciphertext in the constraint system
+        let expected_var =
+            OutputVar::new_input(ark_relations::ns!(cs, "expected"), || Ok(&primitive_result))
+                .unwrap();
+        expected_var.enforce_equal(&result_var).unwrap();
+        decrypted_var
+            .to_fpvar()
+            .unwrap()
+            .enforce_equal(&msg_var.to_fpvar().unwrap())
+            .unwrap();
+
+        assert_eq!(primitive_result.c1, result_var.c1.value().unwrap());
+        assert_eq!(primitive_result.c2, result_var.c2.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    fn encrypt_committed_is_satisfied(value: u64, num_bits: usize, committed: u64) -> bool {
+        let rng = &mut test_rng();
+
+        let rc = mimc7::Parameters {
+            round_constants: mimc7::parameters::get_bn256_round_constants(),
+        };
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, _) = MyEnc::keygen(&parameters, rng).unwrap();
+        let randomness = Randomness::rand(rng);
+        let opening = Fq::rand(rng);
+        let commitment = commit(&rc, committed, opening).unwrap();
+        let primitive_result = MyEnc::encrypt(&parameters, &pk, &value, &randomness).unwrap();
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let parameters_var =
+            ParametersVar::new_constant(ark_relations::ns!(cs, "parameters"), &parameters).unwrap();
+        let rc_var =
+            mimc7::constraints::ParametersVar::new_constant(ark_relations::ns!(cs, "rc"), &rc)
+                .unwrap();
+        let value_var =
+            FpVar::new_witness(ark_relations::ns!(cs, "value"), || Ok(Fq::from(value))).unwrap();
+        let opening_var =
+            FpVar::new_witness(ark_relations::ns!(cs, "opening"), || Ok(opening)).unwrap();
+        let commitment_var =
+            FpVar::new_input(ark_relations::ns!(cs, "commitment"), || Ok(commitment)).unwrap();
+        let randomness_var =
+            RandomnessVar::new_witness(ark_relations::ns!(cs, "randomness"), || Ok(&randomness))
+                .unwrap();
+        let pk_var =
+            PublicKeyVar::new_input(ark_relations::ns!(cs, "public_key"), || Ok(&pk)).unwrap();
+
+        let result_var = MyGadget::encrypt_committed(
+            &parameters_var,
+            &rc_var,
+            &value_var,
+            num_bits,
+            &opening_var,
+            &commitment_var,
+            &randomness_var,
+            &pk_var,
+        )
+        .unwrap();
+        let expected_var =
+            OutputVar::new_input(ark_relations::ns!(cs, "expected"), || Ok(&primitive_result))
+                .unwrap();
+        expected_var.enforce_equal(&result_var).unwrap();
+
+        cs.is_satisfied().unwrap()
+    }
+
+    #[test]
+    fn test_exp_elgamal_encrypt_committed() {
+        assert!(encrypt_committed_is_satisfied(1000, 16, 1000));
+
+        // out of range
+        assert!(!encrypt_committed_is_satisfied(1 << 16, 16, 1 << 16));
+
+        // not the committed value
+        assert!(!encrypt_committed_is_satisfied(1000, 16, 1001));
+    }
+}
@@ -0,0 +1,246 @@
+pub mod constraints;
+
+use crate::gadget::hashes::{mimc7, CRHScheme};
+use crate::gadget::public_encryptions::elgamal::{Parameters, PublicKey, Randomness, SecretKey};
+use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+use crate::Error;
+use ark_crypto_primitives::sponge::Absorb;
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{fields::PrimeField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::collections::HashMap;
+use ark_std::marker::PhantomData;
+use ark_std::ops::{Add, AddAssign, Mul};
+use ark_std::rand::Rng;
+use derivative::Derivative;
+
+/// Largest plaintext bit size recovered by [`AsymmetricEncryptionScheme::decrypt`].
+pub const MAX_DECRYPT_BITS: u32 = 32;
+
+/// Largest plaintext bit size accepted by [`ExpElGamal::decrypt_bounded`], which bounds its
+/// baby-step table to `2^20` entries.
+pub const MAX_BOUNDED_DECRYPT_BITS: u32 = 40;
+
+/// ElGamal with the message in the exponent, `c = (r*G, m*G + r*pk)`. Ciphertexts under the same
+/// key can be added, which adds their plaintexts; decryption solves a small discrete logarithm
+/// and is therefore only feasible for small amounts.
+pub struct ExpElGamal<C: CurveGroup> {
+    _group: PhantomData<C>,
+}
+
+pub type Plaintext = u64;
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Copy(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
+pub struct Ciphertext<C: CurveGroup> {
+    pub c1: C::Affine,
+    pub c2: C::Affine,
+}
+
+impl<C: CurveGroup> Add for Ciphertext<C> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Ciphertext {
+            c1: (self.c1 + other.c1).into_affine(),
+            c2: (self.c2 + other.c2).into_affine(),
+        }
+    }
+}
+
+impl<C: CurveGroup> AddAssign for Ciphertext<C> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+/// Returns the commitment `MiMC(m || opening)` to a plaintext, as opened by
+/// [`constraints::ExpElGamalEncGadget::encrypt_committed`].
+pub fn commit<F: PrimeField + Absorb>(
+    rc: &mimc7::Parameters<F>,
+    m: Plaintext,
+    opening: F,
+) -> Result<F, Error> {
+    mimc7::MiMC::<F>::evaluate(rc, [F::from(m), opening].to_vec())
+}
+
+impl<C: CurveGroup> ExpElGamal<C>
+where
+    C::ScalarField: PrimeField,
+{
+    /// Decrypts a plaintext smaller than `2^num_bits` with baby-step giant-step, taking about
+    /// `2^(num_bits/2)` group operations and as many table entries. `num_bits` is at most
+    /// [`MAX_BOUNDED_DECRYPT_BITS`].
+    pub fn decrypt_bounded(
+        pp: &Parameters<C>,
+        sk: &SecretKey<C>,
+        ciphertext: &Ciphertext<C>,
+        num_bits: u32,
+    ) -> Result<Plaintext, Error> {
+        if num_bits > MAX_BOUNDED_DECRYPT_BITS {
+            return Err(format!("plaintexts have at most {MAX_BOUNDED_DECRYPT_BITS} bits").into());
+        }
+
+        // compute m*G = c2 - secret_key*c1
+        let target = -ciphertext.c1.mul(sk.0) + ciphertext.c2;
+
+        Self::discrete_log(pp, target, num_bits).ok_or_else(|| "plaintext out of range".into())
+    }
+
+    /// Solves `target = m*generator` for `m < 2^num_bits`.
+    pub(crate) fn discrete_log(pp: &Parameters<C>, target: C, num_bits: u32) -> Option<Plaintext> {
+        let baby_steps = 1u64 << num_bits.div_ceil(2);
+        let giant_steps = 1u64 << (num_bits / 2);
+
+        // baby steps b*G for b < baby_steps
+        let mut point = C::zero();
+        let baby = (0..baby_steps)
+            .map(|_| {
+                let current = point;
+                point += pp.generator;
+                current
+            })
+            .collect::<Vec<_>>();
+        let table: HashMap<_, _> = C::normalize_batch(&baby).into_iter().zip(0..).collect();
+
+        // giant steps target - a*baby_steps*G
+        let giant = -point;
+        let mut point = target;
+        for a in 0..giant_steps {
+            if let Some(b) = table.get(&point.into_affine()) {
+                return Some(a * baby_steps + b);
+            }
+            point += giant;
+        }
+        None
+    }
+}
+
+impl<C: CurveGroup> AsymmetricEncryptionScheme for ExpElGamal<C>
+where
+    C::ScalarField: PrimeField,
+{
+    type Parameters = Parameters<C>;
+    type PublicKey = PublicKey<C>;
+    type SecretKey = SecretKey<C>;
+    type Randomness = Randomness<C>;
+    type Plaintext = Plaintext;
+    type Ciphertext = Ciphertext<C>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(Parameters::standard())
+    }
+
+    fn keygen<R: Rng>(
+        pp: &Self::Parameters,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey), Error> {
+        // get a random element from the scalar field
+        let secret_key: <C as Group>::ScalarField = C::ScalarField::rand(rng);
+
+        // compute secret_key*generator to derive the public key
+        let public_key = pp.generator.mul(secret_key).into();
+
+        Ok((public_key, SecretKey(secret_key)))
+    }
+
+    fn encrypt(
+        pp: &Self::Parameters,
+        pk: &Self::PublicKey,
+        message: &Self::Plaintext,
+        r: &Self::Randomness,
+    ) -> Result<Self::Ciphertext, Error> {
+        // compute s = r*pk
+        let s = pk.mul(r.0);
+
+        // compute c1 = r*generator
+        let c1 = pp.generator.mul(r.0).into_affine();
+
+        // compute c2 = m*generator + s
+        let c2 = (pp.generator.mul(C::ScalarField::from(*message)) + s).into_affine();
+
+        Ok(Ciphertext { c1, c2 })
+    }
+
+    /// Decrypts plaintexts smaller than `2^MAX_DECRYPT_BITS`, see [`ExpElGamal::decrypt_bounded`].
+    fn decrypt(
+        pp: &Self::Parameters,
+        sk: &Self::SecretKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::Plaintext, Error> {
+        Self::decrypt_bounded(pp, sk, ciphertext, MAX_DECRYPT_BITS)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_std::{test_rng, UniformRand};
+
+    use ark_ed_on_bn254::EdwardsProjective;
+
+    use crate::gadget::public_encryptions::elgamal::Randomness;
+    use crate::gadget::public_encryptions::exp_elgamal::{ExpElGamal, MAX_BOUNDED_DECRYPT_BITS};
+    use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+
+    type MyEnc = ExpElGamal<EdwardsProjective>;
+
+    #[test]
+    fn test_exp_elgamal_encryption() {
+        let rng = &mut test_rng();
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+
+        for msg in [0, 1, 4_000_000_000] {
+            let r = Randomness::rand(rng);
+            let cipher = MyEnc::encrypt(&parameters, &pk, &msg, &r).unwrap();
+            assert_eq!(msg, MyEnc::decrypt(&parameters, &sk, &cipher).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_exp_elgamal_homomorphic_add() {
+        let rng = &mut test_rng();
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+
+        let amounts = [1200u64, 34, 56_000];
+        let mut sum =
+            MyEnc::encrypt(&parameters, &pk, &amounts[0], &Randomness::rand(rng)).unwrap();
+        for amount in &amounts[1..] {
+            sum += MyEnc::encrypt(&parameters, &pk, amount, &Randomness::rand(rng)).unwrap();
+        }
+
+        assert_eq!(
+            amounts.iter().sum::<u64>(),
+            MyEnc::decrypt(&parameters, &sk, &sum).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_exp_elgamal_decrypt_bound() {
+        let rng = &mut test_rng();
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+        let cipher = MyEnc::encrypt(&parameters, &pk, &300, &Randomness::rand(rng)).unwrap();
+
+        assert_eq!(
+            300,
+            MyEnc::decrypt_bounded(&parameters, &sk, &cipher, 9).unwrap()
+        );
+        assert!(MyEnc::decrypt_bounded(&parameters, &sk, &cipher, 8).is_err());
+
+        // bounds that would need an oversized baby-step table are rejected up front
+        assert!(
+            MyEnc::decrypt_bounded(&parameters, &sk, &cipher, MAX_BOUNDED_DECRYPT_BITS + 1)
+                .is_err()
+        );
+    }
+}
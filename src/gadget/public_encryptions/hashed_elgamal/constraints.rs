@@ -0,0 +1,370 @@
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*, ToConstraintFieldGadget};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use derivative::Derivative;
+
+use crate::gadget::hashes::{
+    constraints::CRHSchemeGadget,
+    mimc7::{self, constraints::MiMCGadget},
+};
+use crate::gadget::public_encryptions::elgamal::constraints::{
+    scalar_mul_le, PublicKeyVar, RandomnessVar, SecretKeyVar,
+};
+use crate::gadget::public_encryptions::hashed_elgamal::{Ciphertext, HashedElGamal, Parameters};
+use crate::gadget::public_encryptions::AsymmetricEncryptionGadget;
+use ark_bn254::Fr;
+use ark_ec::CurveGroup;
+use ark_ff::{fields::PrimeField, ToConstraintField};
+use ark_std::{borrow::Borrow, marker::PhantomData, vec::Vec};
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "C: CurveGroup, GG: CurveVar<C, Fr>"))]
+pub struct ParametersVar<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub generator: GG,
+    pub hash: mimc7::constraints::ParametersVar<Fr>,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> AllocVar<Parameters<C>, Fr> for ParametersVar<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn new_variable<T: Borrow<Parameters<C>>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let prep = f().map(|p| p.borrow().clone());
+        let generator = GG::new_variable(
+            cs.clone(),
+            || prep.as_ref().map(|p| p.generator).map_err(|e| *e),
+            mode,
+        )?;
+        let hash = mimc7::constraints::ParametersVar::new_variable(
+            cs,
+            || prep.as_ref().map(|p| &p.hash).map_err(|e| *e),
+            mode,
+        )?;
+        Ok(Self {
+            generator,
+            hash,
+            _curve: PhantomData,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PlaintextVar(pub Vec<FpVar<Fr>>);
+
+impl AllocVar<Vec<Fr>, Fr> for PlaintextVar {
+    fn new_variable<T: Borrow<Vec<Fr>>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let prep = f().map(|m| m.borrow().clone());
+        Vec::<FpVar<Fr>>::new_variable(
+            cs,
+            || prep.as_ref().map(|m| m.as_slice()).map_err(|e| *e),
+            mode,
+        )
+        .map(Self)
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(
+    Clone(bound = "C: CurveGroup, GG: CurveVar<C, Fr>"),
+    Debug(bound = "C: CurveGroup, GG: CurveVar<C, Fr>")
+)]
+pub struct OutputVar<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub c1: GG,
+    pub c2: Vec<FpVar<Fr>>,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> OutputVar<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    /// Allocates a ciphertext of `len` elements. Unlike [`AllocVar::new_variable`], the shape of
+    /// the circuit does not depend on the assignment, so this also works in setup mode.
+    pub fn new_variable_with_len<T: Borrow<Ciphertext<C>>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        len: usize,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let prep = f().map(|c| c.borrow().clone());
+        if matches!(&prep, Ok(c) if c.c2.len() != len) {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let c1 = GG::new_variable(
+            cs.clone(),
+            || prep.as_ref().map(|c| c.c1).map_err(|e| *e),
+            mode,
+        )?;
+        let c2 = (0..len)
+            .map(|i| {
+                FpVar::new_variable(
+                    cs.clone(),
+                    || prep.as_ref().map(|c| c.c2[i]).map_err(|e| *e),
+                    mode,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            c1,
+            c2,
+            _curve: PhantomData,
+        })
+    }
+}
+
+impl<C, GG> AllocVar<Ciphertext<C>, Fr> for OutputVar<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    /// Allocates a ciphertext as long as the assignment, which is therefore required even in
+    /// setup mode. Use [`OutputVar::new_variable_with_len`] when the length is known.
+    fn new_variable<T: Borrow<Ciphertext<C>>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ciphertext = f()?.borrow().clone();
+        let len = ciphertext.c2.len();
+        Self::new_variable_with_len(cs, || Ok(ciphertext), len, mode)
+    }
+}
+
+impl<C, GG> EqGadget<Fr> for OutputVar<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    #[inline]
+    fn is_eq(&self, other: &Self) -> Result<Boolean<Fr>, SynthesisError> {
+        if self.c2.len() != other.c2.len() {
+            return Ok(Boolean::FALSE);
+        }
+        self.c1.is_eq(&other.c1)?.and(&self.c2.is_eq(&other.c2)?)
+    }
+}
+
+pub struct HashedElGamalEncGadget<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    #[doc(hidden)]
+    _curve: PhantomData<*const C>,
+    _group_var: PhantomData<*const GG>,
+}
+
+impl<C, GG> HashedElGamalEncGadget<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr> + ToConstraintFieldGadget<Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    /// Returns the first `len` pads derived from the shared point.
+    fn pads(
+        parameters: &ParametersVar<C, GG>,
+        shared: &GG,
+        len: usize,
+    ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+        let coordinates = shared.to_constraint_field()?;
+
+        (0..len)
+            .map(|i| {
+                let mut input = coordinates.clone();
+                input.push(FpVar::Constant(Fr::from(i as u64)));
+                MiMCGadget::<Fr>::evaluate(&parameters.hash, &input)
+            })
+            .collect()
+    }
+}
+
+impl<C, GG> AsymmetricEncryptionGadget<HashedElGamal<C>, Fr> for HashedElGamalEncGadget<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    C::ScalarField: PrimeField,
+    C::Affine: ToConstraintField<Fr>,
+    GG: CurveVar<C, Fr> + ToConstraintFieldGadget<Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    type OutputVar = OutputVar<C, GG>;
+    type ParametersVar = ParametersVar<C, GG>;
+    type PlaintextVar = PlaintextVar;
+    type PublicKeyVar = PublicKeyVar<C, GG>;
+    type RandomnessVar = RandomnessVar<Fr>;
+    type SecretKeyVar = SecretKeyVar<Fr>;
+
+    fn encrypt(
+        parameters: &Self::ParametersVar,
+        message: &Self::PlaintextVar,
+        randomness: &Self::RandomnessVar,
+        public_key: &Self::PublicKeyVar,
+    ) -> Result<Self::OutputVar, SynthesisError> {
+        // compute s = randomness*pk
+        let s = scalar_mul_le(&public_key.pk, &randomness.0)?;
+
+        // compute c1 = randomness*generator
+        let c1 = scalar_mul_le(&parameters.generator, &randomness.0)?;
+
+        // compute c2 = m + H(s)
+        let pads = Self::pads(parameters, &s, message.0.len())?;
+        let c2 = message.0.iter().zip(pads).map(|(m, pad)| m + pad).collect();
+
+        Ok(Self::OutputVar {
+            c1,
+            c2,
+            _curve: PhantomData,
+        })
+    }
+
+    fn decrypt(
+        parameters: &Self::ParametersVar,
+        ciphertext: &Self::OutputVar,
+        secret_key: &Self::SecretKeyVar,
+    ) -> Result<Self::PlaintextVar, SynthesisError> {
+        // compute s = secret_key*c1
+        let s = ciphertext.c1.scalar_mul_le(secret_key.0.iter())?;
+
+        // compute m = c2 - H(s)
+        let pads = Self::pads(parameters, &s, ciphertext.c2.len())?;
+        let plaintext = ciphertext
+            .c2
+            .iter()
+            .zip(pads)
+            .map(|(c, pad)| c - pad)
+            .collect();
+
+        Ok(PlaintextVar(plaintext))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_std::{test_rng, UniformRand};
+
+    use ark_ed_on_bn254::{constraints::EdwardsVar, EdwardsProjective, Fq};
+
+    use crate::gadget::public_encryptions::constraints::AsymmetricEncryptionGadget;
+    use crate::gadget::public_encryptions::elgamal::{
+        constraints::{PublicKeyVar, RandomnessVar, SecretKeyVar},
+        Randomness,
+    };
+    use crate::gadget::public_encryptions::hashed_elgamal::{
+        constraints::{HashedElGamalEncGadget, OutputVar, ParametersVar, PlaintextVar},
+        Ciphertext, HashedElGamal,
+    };
+    use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+    use ark_r1cs_std::prelude::*;
+    use ark_relations::r1cs::{ConstraintSystem, SynthesisError, SynthesisMode};
+
+    type MyEnc = HashedElGamal<EdwardsProjective>;
+    type MyGadget = HashedElGamalEncGadget<EdwardsProjective, EdwardsVar>;
+
+    #[test]
+    fn test_hashed_elgamal_gadget() {
+        let rng = &mut test_rng();
+
+        // compute primitive result
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+        let msg: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+        let randomness = Randomness::rand(rng);
+        let primitive_result = MyEnc::encrypt(&parameters, &pk, &msg, &randomness).unwrap();
+
+        // construct constraint system
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let parameters_var = ParametersVar::<EdwardsProjective, EdwardsVar>::new_constant(
+            ark_relations::ns!(cs, "gadget_parameters"),
+            &parameters,
+        )
+        .unwrap();
+        let randomness_var =
+            RandomnessVar::new_witness(ark_relations::ns!(cs, "gadget_randomness"), || {
+                Ok(&randomness)
+            })
+            .unwrap();
+        let msg_var =
+            PlaintextVar::new_witness(ark_relations::ns!(cs, "gadget_message"), || Ok(&msg))
+                .unwrap();
+        let pk_var =
+            PublicKeyVar::new_witness(ark_relations::ns!(cs, "gadget_public_key"), || Ok(&pk))
+                .unwrap();
+        let sk_var =
+            SecretKeyVar::new_witness(ark_relations::ns!(cs, "gadget_secret_key"), || Ok(&sk))
+                .unwrap();
+
+        // use gadget
+        let result_var =
+            MyGadget::encrypt(&parameters_var, &msg_var, &randomness_var, &pk_var).unwrap();
+        let decrypted_var = MyGadget::decrypt(&parameters_var, &result_var, &sk_var).unwrap();
+
+        // check that result equals expected ciphertext in the constraint system
+        let expected_var = OutputVar::<EdwardsProjective, EdwardsVar>::new_input(
+            ark_relations::ns!(cs, "gadget_expected"),
+            || Ok(&primitive_result),
+        )
+        .unwrap();
+        expected_var.enforce_equal(&result_var).unwrap();
+        decrypted_var.0.enforce_equal(&msg_var.0).unwrap();
+
+        assert_eq!(primitive_result.c1, result_var.c1.value().unwrap());
+        assert_eq!(primitive_result.c2, result_var.c2.value().unwrap());
+        assert_eq!(msg, decrypted_var.0.value().unwrap());
+        assert!(cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_output_var_with_len() {
+        let rng = &mut test_rng();
+        let missing = || Err::<Ciphertext<EdwardsProjective>, _>(SynthesisError::AssignmentMissing);
+
+        // setup allocates the ciphertext without an assignment
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let output_var = OutputVar::<EdwardsProjective, EdwardsVar>::new_variable_with_len(
+            ark_relations::ns!(cs, "ciphertext"),
+            missing,
+            3,
+            AllocationMode::Input,
+        )
+        .unwrap();
+        assert_eq!(output_var.c2.len(), 3);
+        assert_eq!(cs.num_instance_variables(), 1 + 2 + 3);
+
+        // an assignment of another length is rejected
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, _) = MyEnc::keygen(&parameters, rng).unwrap();
+        let msg: Vec<Fq> = (0..2).map(|_| Fq::rand(rng)).collect();
+        let cipher = MyEnc::encrypt(&parameters, &pk, &msg, &Randomness::rand(rng)).unwrap();
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let result = OutputVar::<EdwardsProjective, EdwardsVar>::new_variable_with_len(
+            ark_relations::ns!(cs, "ciphertext"),
+            || Ok(&cipher),
+            3,
+            AllocationMode::Input,
+        );
+        assert_eq!(result.unwrap_err(), SynthesisError::Unsatisfiable);
+    }
+}
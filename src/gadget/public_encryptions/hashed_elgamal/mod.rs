@@ -0,0 +1,214 @@
+pub mod constraints;
+
+use crate::gadget::hashes::{mimc7, CRHScheme};
+use crate::gadget::public_encryptions::elgamal::{Randomness, SecretKey};
+use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+use crate::Error;
+use ark_bn254::Fr;
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{fields::PrimeField, ToConstraintField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::ops::Mul;
+use ark_std::rand::Rng;
+use derivative::Derivative;
+
+/// Hybrid ElGamal encrypting field elements: a Diffie-Hellman point is shared with the recipient
+/// and its coordinates are hashed with MiMC into one pad per plaintext element.
+///
+/// MiMC round constants are only defined over the BN254 scalar field, so the curve must be defined
+/// over it (e.g. ed-on-bn254).
+pub struct HashedElGamal<C: CurveGroup> {
+    _group: PhantomData<C>,
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = "C: CurveGroup"))]
+pub struct Parameters<C: CurveGroup> {
+    pub generator: C::Affine,
+    pub hash: mimc7::Parameters<C::BaseField>,
+}
+
+pub type PublicKey<C> = <C as CurveGroup>::Affine;
+
+pub type Plaintext<C> = Vec<<C as CurveGroup>::BaseField>;
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
+pub struct Ciphertext<C: CurveGroup> {
+    /// `r*generator`
+    pub c1: C::Affine,
+    /// `m[i] + MiMC(x, y, i)` where `(x, y) = r*pk`
+    pub c2: Vec<C::BaseField>,
+}
+
+impl<C> HashedElGamal<C>
+where
+    C: CurveGroup<BaseField = Fr>,
+    C::Affine: ToConstraintField<Fr>,
+{
+    /// Returns the first `len` pads derived from the shared point.
+    fn pads(
+        pp: &Parameters<C>,
+        shared: &C::Affine,
+        len: usize,
+    ) -> Result<Vec<C::BaseField>, Error> {
+        let mut coordinates = shared
+            .to_field_elements()
+            .ok_or("cannot convert the shared point to field elements")?;
+        coordinates.push(Fr::from(0u64));
+        let counter = coordinates.len() - 1;
+
+        (0..len)
+            .map(|i| {
+                coordinates[counter] = Fr::from(i as u64);
+                mimc7::MiMC::<Fr>::evaluate(&pp.hash, coordinates.clone())
+            })
+            .collect()
+    }
+}
+
+impl<C> AsymmetricEncryptionScheme for HashedElGamal<C>
+where
+    C: CurveGroup<BaseField = Fr>,
+    C::ScalarField: PrimeField,
+    C::Affine: ToConstraintField<Fr>,
+{
+    type Parameters = Parameters<C>;
+    type PublicKey = PublicKey<C>;
+    type SecretKey = SecretKey<C>;
+    type Randomness = Randomness<C>;
+    type Plaintext = Plaintext<C>;
+    type Ciphertext = Ciphertext<C>;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(Parameters {
+            generator: C::generator().into(),
+            hash: mimc7::Parameters {
+                round_constants: mimc7::parameters::get_bn256_round_constants(),
+            },
+        })
+    }
+
+    fn keygen<R: Rng>(
+        pp: &Self::Parameters,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey), Error> {
+        // get a random element from the scalar field
+        let secret_key: <C as Group>::ScalarField = C::ScalarField::rand(rng);
+
+        // compute secret_key*generator to derive the public key
+        let public_key = pp.generator.mul(secret_key).into();
+
+        Ok((public_key, SecretKey(secret_key)))
+    }
+
+    fn encrypt(
+        pp: &Self::Parameters,
+        pk: &Self::PublicKey,
+        message: &Self::Plaintext,
+        r: &Self::Randomness,
+    ) -> Result<Self::Ciphertext, Error> {
+        // compute s = r*pk
+        let s = pk.mul(r.0).into_affine();
+
+        // compute c1 = r*generator
+        let c1 = pp.generator.mul(r.0).into_affine();
+
+        // compute c2 = m + H(s)
+        let pads = Self::pads(pp, &s, message.len())?;
+        let c2 = message.iter().zip(pads).map(|(m, pad)| *m + pad).collect();
+
+        Ok(Ciphertext { c1, c2 })
+    }
+
+    fn decrypt(
+        pp: &Self::Parameters,
+        sk: &Self::SecretKey,
+        ciphertext: &Self::Ciphertext,
+    ) -> Result<Self::Plaintext, Error> {
+        // compute s = secret_key * c1
+        let s = ciphertext.c1.mul(sk.0).into_affine();
+
+        // compute message = c2 - H(s)
+        let pads = Self::pads(pp, &s, ciphertext.c2.len())?;
+        let message = ciphertext
+            .c2
+            .iter()
+            .zip(pads)
+            .map(|(c, pad)| *c - pad)
+            .collect();
+
+        Ok(message)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::{test_rng, UniformRand};
+
+    use ark_ed_on_bn254::{EdwardsProjective, Fq};
+
+    use crate::gadget::public_encryptions::elgamal::Randomness;
+    use crate::gadget::public_encryptions::hashed_elgamal::{
+        Ciphertext, HashedElGamal, Parameters,
+    };
+    use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+
+    #[test]
+    fn test_hashed_elgamal_encryption() {
+        let rng = &mut test_rng();
+
+        type MyEnc = HashedElGamal<EdwardsProjective>;
+
+        // setup and key generation
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+        let (_, other_sk) = MyEnc::keygen(&parameters, rng).unwrap();
+
+        // get a random msg and encryption randomness
+        let msg: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+        let r = Randomness::rand(rng);
+
+        // encrypt and decrypt the message
+        let cipher = MyEnc::encrypt(&parameters, &pk, &msg, &r).unwrap();
+        let check_msg = MyEnc::decrypt(&parameters, &sk, &cipher).unwrap();
+        assert_eq!(msg, check_msg);
+
+        // every element gets its own pad
+        assert_ne!(cipher.c2[0] - msg[0], cipher.c2[1] - msg[1]);
+
+        // another key does not decrypt
+        let wrong_msg = MyEnc::decrypt(&parameters, &other_sk, &cipher).unwrap();
+        assert_ne!(msg, wrong_msg);
+    }
+
+    #[test]
+    fn test_hashed_elgamal_serialization() {
+        let rng = &mut test_rng();
+
+        type MyEnc = HashedElGamal<EdwardsProjective>;
+
+        let parameters = MyEnc::setup(rng).unwrap();
+        let (pk, sk) = MyEnc::keygen(&parameters, rng).unwrap();
+        let msg: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+        let cipher = MyEnc::encrypt(&parameters, &pk, &msg, &Randomness::rand(rng)).unwrap();
+
+        let mut bytes = Vec::new();
+        parameters.serialize_compressed(&mut bytes).unwrap();
+        let parameters =
+            Parameters::<EdwardsProjective>::deserialize_compressed(bytes.as_slice()).unwrap();
+
+        let mut bytes = Vec::new();
+        cipher.serialize_compressed(&mut bytes).unwrap();
+        let restored =
+            Ciphertext::<EdwardsProjective>::deserialize_compressed(bytes.as_slice()).unwrap();
+        assert_eq!(cipher, restored);
+        assert_eq!(msg, MyEnc::decrypt(&parameters, &sk, &restored).unwrap());
+    }
+}
@@ -3,6 +3,8 @@ pub mod constraints;
 pub use constraints::*;
 
 pub mod elgamal;
+pub mod exp_elgamal;
+pub mod hashed_elgamal;
 
 use crate::Error;
 use ark_std::rand::Rng;
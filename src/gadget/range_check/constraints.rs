@@ -0,0 +1,252 @@
+use crate::gadget::range_check::to_limbs;
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::SynthesisError;
+
+/// Witnesses of a constant stay constants, so that checks on constants add no constraints.
+fn mode_for<F: PrimeField>(x: &FpVar<F>) -> AllocationMode {
+    if x.is_constant() {
+        AllocationMode::Constant
+    } else {
+        AllocationMode::Witness
+    }
+}
+
+/// Enforces `x < 2^num_bits` by decomposing `x` into bits, and returns the little-endian bits.
+/// Costs one constraint per bit.
+pub fn enforce_bits<F: PrimeField>(
+    x: &FpVar<F>,
+    num_bits: usize,
+) -> Result<Vec<Boolean<F>>, SynthesisError> {
+    // the recomposition must not wrap around the modulus
+    assert!(num_bits < F::MODULUS_BIT_SIZE as usize);
+
+    let cs = x.cs();
+    let mode = mode_for(x);
+    let value_bits = x.value().map(|v| v.into_bigint().to_bits_le());
+    let bits = (0..num_bits)
+        .map(|i| {
+            Boolean::new_variable(
+                ark_relations::ns!(cs, "bit"),
+                || value_bits.as_ref().map(|b| b[i]).map_err(|e| *e),
+                mode,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Boolean::le_bits_to_fp_var(&bits)?.enforce_equal(x)?;
+    Ok(bits)
+}
+
+/// Enforces `x < 2^num_bits` by decomposing `x` into limbs of `limb_bits` bits, each checked
+/// without lookups by the vanishing polynomial `l*(l-1)*...*(l-2^limb_bits+1) = 0`, and returns
+/// the little-endian limbs. Costs `2^limb_bits - 1` constraints per limb, so it only pays off over
+/// [`enforce_bits`] when the limbs themselves are needed, e.g. for limb-wise arithmetic.
+pub fn enforce_limbs<F: PrimeField>(
+    x: &FpVar<F>,
+    num_bits: usize,
+    limb_bits: usize,
+) -> Result<Vec<FpVar<F>>, SynthesisError> {
+    assert!(num_bits < F::MODULUS_BIT_SIZE as usize);
+    assert!(limb_bits > 0 && limb_bits <= 8);
+
+    let cs = x.cs();
+    let mode = mode_for(x);
+    let value_limbs = x.value().map(|v| to_limbs(&v, num_bits, limb_bits));
+    let mut sum = FpVar::zero();
+    let mut shift = F::one();
+    let mut limbs = Vec::new();
+    for (i, start) in (0..num_bits).step_by(limb_bits).enumerate() {
+        let width = limb_bits.min(num_bits - start);
+        let limb = FpVar::new_variable(
+            ark_relations::ns!(cs, "limb"),
+            || value_limbs.as_ref().map(|l| l[i]).map_err(|e| *e),
+            mode,
+        )?;
+
+        let mut vanishing = limb.clone();
+        for j in 1..(1u64 << width) {
+            vanishing *= &limb - F::from(j);
+        }
+        vanishing.enforce_equal(&FpVar::zero())?;
+
+        sum += &limb * shift;
+        shift *= F::from(1u64 << limb_bits);
+        limbs.push(limb);
+    }
+    sum.enforce_equal(x)?;
+    Ok(limbs)
+}
+
+/// Returns whether `a < b`, enforcing that both operands are smaller than `2^num_bits`.
+pub fn is_less_than<F: PrimeField>(
+    a: &FpVar<F>,
+    b: &FpVar<F>,
+    num_bits: usize,
+) -> Result<Boolean<F>, SynthesisError> {
+    assert!(num_bits + 1 < F::MODULUS_BIT_SIZE as usize);
+
+    enforce_bits(a, num_bits)?;
+    enforce_bits(b, num_bits)?;
+    // a - b + 2^num_bits lies in [1, 2^(num_bits+1)), and its top bit is set iff a >= b
+    let shifted = a - b
+        + F::from(F::BigInt::from_bits_le(&{
+            let mut bits = vec![false; num_bits];
+            bits.push(true);
+            bits
+        }));
+    let bits = enforce_bits(&shifted, num_bits + 1)?;
+    Ok(bits[num_bits].not())
+}
+
+/// Returns whether `a <= b`, enforcing that both operands are smaller than `2^num_bits`.
+pub fn is_less_or_equal<F: PrimeField>(
+    a: &FpVar<F>,
+    b: &FpVar<F>,
+    num_bits: usize,
+) -> Result<Boolean<F>, SynthesisError> {
+    Ok(is_less_than(b, a, num_bits)?.not())
+}
+
+/// Enforces `a < b` for operands smaller than `2^num_bits`.
+pub fn enforce_less_than<F: PrimeField>(
+    a: &FpVar<F>,
+    b: &FpVar<F>,
+    num_bits: usize,
+) -> Result<(), SynthesisError> {
+    is_less_than(a, b, num_bits)?.enforce_equal(&Boolean::TRUE)
+}
+
+/// Enforces `a <= b` for operands smaller than `2^num_bits`.
+pub fn enforce_less_or_equal<F: PrimeField>(
+    a: &FpVar<F>,
+    b: &FpVar<F>,
+    num_bits: usize,
+) -> Result<(), SynthesisError> {
+    is_less_or_equal(a, b, num_bits)?.enforce_equal(&Boolean::TRUE)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_ff::Field;
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisMode};
+
+    use super::*;
+
+    fn witness(cs: &ConstraintSystemRef<Fr>, x: Fr) -> FpVar<Fr> {
+        FpVar::new_witness(ark_relations::ns!(cs, "x"), || Ok(x)).unwrap()
+    }
+
+    fn pow2(k: u32) -> Fr {
+        Fr::from(2u64).pow([k as u64])
+    }
+
+    #[test]
+    fn test_enforce_bits_boundary() {
+        for (x, expected) in [
+            (Fr::from(0u64), true),
+            (pow2(16) - Fr::from(1u64), true),
+            (pow2(16), false),
+            (-Fr::from(1u64), false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let bits = enforce_bits(&witness(&cs, x), 16).unwrap();
+            assert_eq!(bits.len(), 16);
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_enforce_limbs_boundary() {
+        for (x, expected) in [
+            (Fr::from(0u64), true),
+            (pow2(10) - Fr::from(1u64), true),
+            (pow2(10), false),
+            (-Fr::from(1u64), false),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let limbs = enforce_limbs(&witness(&cs, x), 10, 4).unwrap();
+            assert_eq!(limbs.len(), 3);
+            assert_eq!(cs.is_satisfied().unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_range_check_constants() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let x = FpVar::constant(Fr::from(300u64));
+        enforce_bits(&x, 9).unwrap();
+        enforce_limbs(&x, 9, 3).unwrap();
+        assert_eq!(cs.num_constraints(), 0);
+    }
+
+    #[test]
+    fn test_range_check_missing_assignment() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        let x = FpVar::new_witness(ark_relations::ns!(cs, "x"), || {
+            Err::<Fr, _>(SynthesisError::AssignmentMissing)
+        })
+        .unwrap();
+        enforce_bits(&x, 8).unwrap();
+        enforce_limbs(&x, 8, 2).unwrap();
+        assert!(is_less_than(&x, &x, 8).is_ok());
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let max = pow2(32) - Fr::from(1u64);
+        for (a, b) in [
+            (Fr::from(3u64), Fr::from(5u64)),
+            (Fr::from(5u64), Fr::from(5u64)),
+            (Fr::from(6u64), Fr::from(5u64)),
+            (Fr::from(0u64), max),
+            (max, Fr::from(0u64)),
+            (max, max),
+        ] {
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let a_var = witness(&cs, a);
+            let b_var = witness(&cs, b);
+            assert_eq!(
+                is_less_than(&a_var, &b_var, 32).unwrap().value().unwrap(),
+                a < b
+            );
+            assert_eq!(
+                is_less_or_equal(&a_var, &b_var, 32)
+                    .unwrap()
+                    .value()
+                    .unwrap(),
+                a <= b
+            );
+            assert!(cs.is_satisfied().unwrap());
+        }
+    }
+
+    #[test]
+    fn test_enforce_comparisons() {
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = witness(&cs, Fr::from(5u64));
+        let b = witness(&cs, Fr::from(5u64));
+        enforce_less_or_equal(&a, &b, 8).unwrap();
+        assert!(cs.is_satisfied().unwrap());
+        enforce_less_than(&a, &b, 8).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+
+    #[test]
+    fn test_comparison_overflow() {
+        // an operand out of range cannot be passed off as small by wrapping around the modulus
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = witness(&cs, -Fr::from(1u64));
+        let b = witness(&cs, Fr::from(5u64));
+        let _ = is_less_than(&a, &b, 8).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let a = witness(&cs, Fr::from(3u64));
+        let b = witness(&cs, pow2(8));
+        enforce_less_than(&a, &b, 8).unwrap();
+        assert!(!cs.is_satisfied().unwrap());
+    }
+}
@@ -0,0 +1,49 @@
+//! Range checks `x < 2^k` and comparisons of bounded field elements, e.g. prices and block counts.
+//!
+//! Field elements have no order, so a comparison is only meaningful for operands known to be
+//! smaller than `2^k` for some `k` well below the field size; every helper takes that bound.
+
+pub mod constraints;
+
+use ark_ff::{BigInteger, PrimeField};
+
+/// Returns whether `x` is smaller than `2^num_bits`.
+pub fn fits_in_bits<F: PrimeField>(x: &F, num_bits: usize) -> bool {
+    x.into_bigint()
+        .to_bits_le()
+        .iter()
+        .skip(num_bits)
+        .all(|b| !b)
+}
+
+/// Splits the low `num_bits` bits of `x` into little-endian limbs of `limb_bits` bits each, the
+/// last limb holding the remaining bits.
+pub fn to_limbs<F: PrimeField>(x: &F, num_bits: usize, limb_bits: usize) -> Vec<F> {
+    let bits = x.into_bigint().to_bits_le();
+    (0..num_bits)
+        .step_by(limb_bits)
+        .map(|start| {
+            let end = (start + limb_bits).min(num_bits);
+            F::from_bigint(F::BigInt::from_bits_le(&bits[start..end])).unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use ark_bn254::Fr;
+
+    use crate::gadget::range_check::{fits_in_bits, to_limbs};
+
+    #[test]
+    fn test_limbs() {
+        let x = Fr::from(0b1_1011_0110u64);
+        assert_eq!(
+            to_limbs(&x, 9, 4),
+            vec![Fr::from(0b0110u64), Fr::from(0b1011u64), Fr::from(1u64)]
+        );
+        assert!(fits_in_bits(&x, 9));
+        assert!(!fits_in_bits(&x, 8));
+        assert!(fits_in_bits(&-Fr::from(1u64), 254));
+    }
+}
@@ -0,0 +1,20 @@
+use crate::gadget::signatures::SignatureScheme;
+
+use ark_r1cs_std::prelude::*;
+use ark_relations::r1cs::SynthesisError;
+
+use ark_ff::fields::Field;
+
+pub trait SigVerifyGadget<S: SignatureScheme, ConstraintF: Field> {
+    type ParametersVar: AllocVar<S::Parameters, ConstraintF> + Clone;
+    type PublicKeyVar: AllocVar<S::PublicKey, ConstraintF> + Clone;
+    type SignatureVar: AllocVar<S::Signature, ConstraintF> + Clone;
+    type MessageVar: ?Sized;
+
+    fn verify(
+        parameters: &Self::ParametersVar,
+        public_key: &Self::PublicKeyVar,
+        message: &Self::MessageVar,
+        signature: &Self::SignatureVar,
+    ) -> Result<Boolean<ConstraintF>, SynthesisError>;
+}
@@ -0,0 +1,244 @@
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*, ToConstraintFieldGadget};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use derivative::Derivative;
+
+use crate::gadget::hashes::{
+    constraints::CRHSchemeGadget,
+    mimc7::{self, constraints::MiMCGadget},
+};
+use crate::gadget::public_encryptions::elgamal::constraints::scalar_mul_le;
+use crate::gadget::signatures::eddsa::{EdDSA, Parameters, PublicKey, Signature};
+use crate::gadget::signatures::SigVerifyGadget;
+use ark_bn254::Fr;
+use ark_ec::CurveGroup;
+use ark_ff::{fields::PrimeField, BigInteger, One, ToConstraintField};
+use ark_std::{borrow::Borrow, marker::PhantomData, vec::Vec};
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "C: CurveGroup, GG: CurveVar<C, Fr>"))]
+pub struct ParametersVar<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub generator: GG,
+    pub hash: mimc7::constraints::ParametersVar<Fr>,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> AllocVar<Parameters<C>, Fr> for ParametersVar<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn new_variable<T: Borrow<Parameters<C>>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let prep = f().map(|p| p.borrow().clone());
+        let generator = GG::new_variable(
+            cs.clone(),
+            || prep.as_ref().map(|p| p.generator).map_err(|e| *e),
+            mode,
+        )?;
+        let hash = mimc7::constraints::ParametersVar::new_variable(
+            cs,
+            || prep.as_ref().map(|p| &p.hash).map_err(|e| *e),
+            mode,
+        )?;
+        Ok(Self {
+            generator,
+            hash,
+            _curve: PhantomData,
+        })
+    }
+}
+
+#[derive(Derivative)]
+#[derivative(Clone(bound = "C: CurveGroup, GG: CurveVar<C, Fr>"))]
+pub struct PublicKeyVar<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub pk: GG,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> AllocVar<PublicKey<C>, Fr> for PublicKeyVar<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn new_variable<T: Borrow<PublicKey<C>>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let pk = GG::new_variable(cs, f, mode)?;
+        Ok(Self {
+            pk,
+            _curve: PhantomData,
+        })
+    }
+}
+
+/// Signature with `s` as little-endian bits, constrained to be smaller than the scalar modulus so
+/// that signatures are not malleable.
+#[derive(Derivative)]
+#[derivative(Clone(bound = "C: CurveGroup, GG: CurveVar<C, Fr>"))]
+pub struct SignatureVar<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    pub r: GG,
+    pub s: Vec<Boolean<Fr>>,
+    pub _curve: PhantomData<C>,
+}
+
+impl<C, GG> AllocVar<Signature<C>, Fr> for SignatureVar<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    GG: CurveVar<C, Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    fn new_variable<T: Borrow<Signature<C>>>(
+        cs: impl Into<Namespace<Fr>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        let ns = cs.into();
+        let cs = ns.cs();
+        let prep = f().map(|sig| sig.borrow().clone());
+        let r = GG::new_variable(
+            cs.clone(),
+            || prep.as_ref().map(|sig| sig.r).map_err(|e| *e),
+            mode,
+        )?;
+
+        let num_bits = C::ScalarField::MODULUS_BIT_SIZE as usize;
+        let bits = prep.map(|sig| sig.s.into_bigint().to_bits_le());
+        let s = (0..num_bits)
+            .map(|i| {
+                Boolean::new_variable(
+                    ark_relations::ns!(cs, "s_bit"),
+                    || bits.as_ref().map(|b| b[i]).map_err(|e| *e),
+                    mode,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Boolean::enforce_smaller_or_equal_than_le(&s, (-C::ScalarField::one()).into_bigint())?;
+
+        Ok(Self {
+            r,
+            s,
+            _curve: PhantomData,
+        })
+    }
+}
+
+pub struct EdDSAVerifyGadget<C: CurveGroup<BaseField = Fr>, GG: CurveVar<C, Fr>>
+where
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    #[doc(hidden)]
+    _curve: PhantomData<*const C>,
+    _group_var: PhantomData<*const GG>,
+}
+
+impl<C, GG> SigVerifyGadget<EdDSA<C>, Fr> for EdDSAVerifyGadget<C, GG>
+where
+    C: CurveGroup<BaseField = Fr>,
+    C::ScalarField: PrimeField,
+    C::Affine: ToConstraintField<Fr>,
+    GG: CurveVar<C, Fr> + ToConstraintFieldGadget<Fr>,
+    for<'a> &'a GG: GroupOpsBounds<'a, C, GG>,
+{
+    type ParametersVar = ParametersVar<C, GG>;
+    type PublicKeyVar = PublicKeyVar<C, GG>;
+    type SignatureVar = SignatureVar<C, GG>;
+    type MessageVar = [FpVar<Fr>];
+
+    fn verify(
+        parameters: &Self::ParametersVar,
+        public_key: &Self::PublicKeyVar,
+        message: &Self::MessageVar,
+        signature: &Self::SignatureVar,
+    ) -> Result<Boolean<Fr>, SynthesisError> {
+        // compute e = MiMC(R || pk || m)
+        let mut input = signature.r.to_constraint_field()?;
+        input.extend(public_key.pk.to_constraint_field()?);
+        input.extend_from_slice(message);
+        let e = MiMCGadget::<Fr>::evaluate(&parameters.hash, &input)?;
+
+        // check s*generator == R + e*pk; for pk in the prime-order subgroup, multiplying by the
+        // unreduced e matches the native reduction into the scalar field
+        let lhs = scalar_mul_le(&parameters.generator, &signature.s)?;
+        let rhs = signature.r.clone() + scalar_mul_le(&public_key.pk, &e.to_bits_le()?)?;
+
+        lhs.is_eq(&rhs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_std::{test_rng, UniformRand};
+
+    use ark_ed_on_bn254::{constraints::EdwardsVar, EdwardsProjective, Fq};
+
+    use crate::gadget::signatures::eddsa::{
+        constraints::{EdDSAVerifyGadget, ParametersVar, PublicKeyVar, SignatureVar},
+        EdDSA,
+    };
+    use crate::gadget::signatures::{SigVerifyGadget, SignatureScheme};
+    use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+    use ark_relations::r1cs::ConstraintSystem;
+
+    type MySig = EdDSA<EdwardsProjective>;
+    type MyGadget = EdDSAVerifyGadget<EdwardsProjective, EdwardsVar>;
+
+    #[test]
+    fn test_eddsa_verify_gadget() {
+        let rng = &mut test_rng();
+
+        let parameters = MySig::setup(rng).unwrap();
+        let (pk, sk) = MySig::keygen(&parameters, rng).unwrap();
+        let msg: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+        let signature = MySig::sign(&parameters, &sk, &msg).unwrap();
+
+        let mut other_msg = msg.clone();
+        other_msg[0] += Fq::from(1u64);
+
+        for (message, valid) in [(&msg, true), (&other_msg, false)] {
+            let cs = ConstraintSystem::<Fq>::new_ref();
+            let parameters_var = ParametersVar::<EdwardsProjective, EdwardsVar>::new_constant(
+                ark_relations::ns!(cs, "parameters"),
+                &parameters,
+            )
+            .unwrap();
+            let pk_var =
+                PublicKeyVar::new_input(ark_relations::ns!(cs, "public_key"), || Ok(&pk)).unwrap();
+            let msg_var = message
+                .iter()
+                .map(|m| FpVar::new_input(ark_relations::ns!(cs, "message"), || Ok(m)).unwrap())
+                .collect::<Vec<_>>();
+            let signature_var =
+                SignatureVar::new_witness(ark_relations::ns!(cs, "signature"), || Ok(&signature))
+                    .unwrap();
+
+            let result =
+                MyGadget::verify(&parameters_var, &pk_var, &msg_var, &signature_var).unwrap();
+
+            assert_eq!(result.value().unwrap(), valid);
+            assert_eq!(
+                MySig::verify(&parameters, &pk, message, &signature).unwrap(),
+                valid
+            );
+            result.enforce_equal(&Boolean::TRUE).unwrap();
+            assert_eq!(cs.is_satisfied().unwrap(), valid);
+        }
+    }
+}
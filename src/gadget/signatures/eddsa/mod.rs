@@ -0,0 +1,224 @@
+pub mod constraints;
+
+use crate::gadget::hashes::{mimc7, CRHScheme};
+use crate::gadget::signatures::SignatureScheme;
+use crate::Error;
+use ark_bn254::Fr;
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{fields::PrimeField, BigInteger, ToConstraintField, UniformRand};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::ops::Mul;
+use ark_std::rand::Rng;
+use derivative::Derivative;
+
+/// Schnorr-style EdDSA with MiMC as the hash: `R = k*G`, `s = k + e*sk` where
+/// `e = MiMC(R || pk || m)` and the nonce `k` is derived deterministically from `sk` and `m`.
+///
+/// MiMC round constants are only defined over the BN254 scalar field, so the curve must be defined
+/// over it (e.g. ed-on-bn254).
+pub struct EdDSA<C: CurveGroup> {
+    _group: PhantomData<C>,
+}
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(Clone(bound = "C: CurveGroup"))]
+pub struct Parameters<C: CurveGroup> {
+    pub generator: C::Affine,
+    pub hash: mimc7::Parameters<C::BaseField>,
+}
+
+pub type PublicKey<C> = <C as CurveGroup>::Affine;
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
+pub struct SecretKey<C: CurveGroup>(pub C::ScalarField);
+
+#[derive(Derivative, CanonicalSerialize, CanonicalDeserialize)]
+#[derivative(
+    Clone(bound = "C: CurveGroup"),
+    Debug(bound = "C: CurveGroup"),
+    PartialEq(bound = "C: CurveGroup")
+)]
+pub struct Signature<C: CurveGroup> {
+    pub r: C::Affine,
+    pub s: C::ScalarField,
+}
+
+pub type Message = [Fr];
+
+/// Reduces a base field element into the scalar field.
+fn to_scalar<C: CurveGroup>(x: Fr) -> C::ScalarField
+where
+    C::ScalarField: PrimeField,
+{
+    C::ScalarField::from_le_bytes_mod_order(&x.into_bigint().to_bytes_le())
+}
+
+impl<C> EdDSA<C>
+where
+    C: CurveGroup<BaseField = Fr>,
+    C::ScalarField: PrimeField,
+    C::Affine: ToConstraintField<Fr>,
+{
+    /// Returns the challenge `MiMC(R || pk || m)` as a base field element.
+    pub(crate) fn challenge(
+        pp: &Parameters<C>,
+        r: &C::Affine,
+        pk: &PublicKey<C>,
+        message: &Message,
+    ) -> Result<Fr, Error> {
+        let mut input = r
+            .to_field_elements()
+            .ok_or("cannot convert R to field elements")?;
+        input.extend(
+            pk.to_field_elements()
+                .ok_or("cannot convert the public key to field elements")?,
+        );
+        input.extend_from_slice(message);
+        mimc7::MiMC::<Fr>::evaluate(&pp.hash, input)
+    }
+
+    /// Returns the nonce `k` for signing `message` with `sk`. A single base field element reduced
+    /// into the smaller scalar field gives a biased nonce, which leaks the secret key over many
+    /// signatures, so `k` is reduced from the 512 bits of `MiMC(0 || sk || m) || MiMC(1 || sk || m)`
+    /// instead.
+    fn nonce(
+        pp: &Parameters<C>,
+        sk: &SecretKey<C>,
+        message: &Message,
+    ) -> Result<C::ScalarField, Error> {
+        let sk = Fr::from_le_bytes_mod_order(&sk.0.into_bigint().to_bytes_le());
+        let mut bytes = Vec::new();
+        for tag in 0..2u64 {
+            let mut input = vec![Fr::from(tag), sk];
+            input.extend_from_slice(message);
+            bytes.extend(
+                mimc7::MiMC::<Fr>::evaluate(&pp.hash, input)?
+                    .into_bigint()
+                    .to_bytes_le(),
+            );
+        }
+        Ok(C::ScalarField::from_le_bytes_mod_order(&bytes))
+    }
+}
+
+impl<C> SignatureScheme for EdDSA<C>
+where
+    C: CurveGroup<BaseField = Fr>,
+    C::ScalarField: PrimeField,
+    C::Affine: ToConstraintField<Fr>,
+{
+    type Parameters = Parameters<C>;
+    type PublicKey = PublicKey<C>;
+    type SecretKey = SecretKey<C>;
+    type Signature = Signature<C>;
+    type Message = Message;
+
+    fn setup<R: Rng>(_rng: &mut R) -> Result<Self::Parameters, Error> {
+        Ok(Parameters {
+            generator: C::generator().into(),
+            hash: mimc7::Parameters {
+                round_constants: mimc7::parameters::get_bn256_round_constants(),
+            },
+        })
+    }
+
+    fn keygen<R: Rng>(
+        pp: &Self::Parameters,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey), Error> {
+        // get a random element from the scalar field
+        let secret_key: <C as Group>::ScalarField = C::ScalarField::rand(rng);
+
+        // compute secret_key*generator to derive the public key
+        let public_key = pp.generator.mul(secret_key).into();
+
+        Ok((public_key, SecretKey(secret_key)))
+    }
+
+    fn sign(
+        pp: &Self::Parameters,
+        sk: &Self::SecretKey,
+        message: &Self::Message,
+    ) -> Result<Self::Signature, Error> {
+        let pk = pp.generator.mul(sk.0).into_affine();
+
+        // derive the nonce k from sk and m
+        let k = Self::nonce(pp, sk, message)?;
+
+        // compute R = k*generator
+        let r = pp.generator.mul(k).into_affine();
+
+        // compute s = k + e*sk
+        let e = to_scalar::<C>(Self::challenge(pp, &r, &pk, message)?);
+        let s = k + e * sk.0;
+
+        Ok(Signature { r, s })
+    }
+
+    fn verify(
+        pp: &Self::Parameters,
+        pk: &Self::PublicKey,
+        message: &Self::Message,
+        signature: &Self::Signature,
+    ) -> Result<bool, Error> {
+        // check s*generator == R + e*pk
+        let e = to_scalar::<C>(Self::challenge(pp, &signature.r, pk, message)?);
+        Ok(pp.generator.mul(signature.s) == pk.mul(e) + signature.r)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ark_std::{test_rng, UniformRand};
+
+    use ark_ed_on_bn254::{EdwardsProjective, Fq};
+
+    use crate::gadget::signatures::eddsa::EdDSA;
+    use crate::gadget::signatures::SignatureScheme;
+
+    type MySig = EdDSA<EdwardsProjective>;
+
+    #[test]
+    fn test_eddsa_signature() {
+        let rng = &mut test_rng();
+
+        let parameters = MySig::setup(rng).unwrap();
+        let (pk, sk) = MySig::keygen(&parameters, rng).unwrap();
+        let (other_pk, _) = MySig::keygen(&parameters, rng).unwrap();
+
+        let msg: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+        let signature = MySig::sign(&parameters, &sk, &msg).unwrap();
+        assert!(MySig::verify(&parameters, &pk, &msg, &signature).unwrap());
+
+        // signing is deterministic
+        assert_eq!(signature, MySig::sign(&parameters, &sk, &msg).unwrap());
+
+        // another message or key does not verify
+        let mut other_msg = msg.clone();
+        other_msg[1] += Fq::from(1u64);
+        assert!(!MySig::verify(&parameters, &pk, &other_msg, &signature).unwrap());
+        assert!(!MySig::verify(&parameters, &other_pk, &msg, &signature).unwrap());
+    }
+
+    #[test]
+    fn test_eddsa_nonce() {
+        let rng = &mut test_rng();
+
+        let parameters = MySig::setup(rng).unwrap();
+        let (_, sk) = MySig::keygen(&parameters, rng).unwrap();
+        let (_, other_sk) = MySig::keygen(&parameters, rng).unwrap();
+        let msg: Vec<Fq> = (0..3).map(|_| Fq::rand(rng)).collect();
+
+        // the nonce is deterministic and depends on both the key and the message
+        let k = MySig::nonce(&parameters, &sk, &msg).unwrap();
+        assert_eq!(k, MySig::nonce(&parameters, &sk, &msg).unwrap());
+        assert_ne!(k, MySig::nonce(&parameters, &other_sk, &msg).unwrap());
+        assert_ne!(k, MySig::nonce(&parameters, &sk, &msg[1..]).unwrap());
+    }
+}
@@ -0,0 +1,35 @@
+pub mod constraints;
+pub use constraints::*;
+
+pub mod eddsa;
+
+use crate::Error;
+use ark_std::rand::Rng;
+
+pub trait SignatureScheme {
+    type Parameters;
+    type PublicKey;
+    type SecretKey;
+    type Signature;
+    type Message: ?Sized;
+
+    fn setup<R: Rng>(rng: &mut R) -> Result<Self::Parameters, Error>;
+
+    fn keygen<R: Rng>(
+        pp: &Self::Parameters,
+        rng: &mut R,
+    ) -> Result<(Self::PublicKey, Self::SecretKey), Error>;
+
+    fn sign(
+        pp: &Self::Parameters,
+        sk: &Self::SecretKey,
+        message: &Self::Message,
+    ) -> Result<Self::Signature, Error>;
+
+    fn verify(
+        pp: &Self::Parameters,
+        pk: &Self::PublicKey,
+        message: &Self::Message,
+        signature: &Self::Signature,
+    ) -> Result<bool, Error>;
+}
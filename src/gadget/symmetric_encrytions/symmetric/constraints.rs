@@ -8,8 +8,8 @@ use ark_relations::r1cs::{Namespace, SynthesisError};
 use super::{SymmetricEncryptionScheme, Randomness, SymmetricKey, Ciphertext, Plaintext};
 use crate::gadget::{
     hashes::{
-        mimc7::constraints::{ParametersVar, TwoToOneMiMCGadget}, 
-        constraints::TwoToOneCRHSchemeGadget,
+        mimc7::constraints::{MiMCGadget, ParametersVar, TwoToOneMiMCGadget}, 
+        constraints::{CRHSchemeGadget, TwoToOneCRHSchemeGadget},
     }, 
     symmetric_encrytions::constraints::SymmetricEncryptionGadget,
 };
@@ -202,6 +202,35 @@ where
     }
 }
 
+impl<F> SymmetricEncryptionSchemeGadget<F>
+where
+    F: PrimeField + Absorb,
+{
+    /// Gadget for [`SymmetricEncryptionScheme::encrypt_block`]; `index` may be a witness or public
+    /// variable, e.g. the index of a selectively disclosed block.
+    pub fn encrypt_block(
+        params: &ParametersVar<F>,
+        r: &RandomnessVar<F>,
+        k: &SymmetricKeyVar<F>,
+        index: &FpVar<F>,
+        m: &PlaintextVar<F>,
+    ) -> Result<CiphertextVar<F>, SynthesisError> {
+        let h = MiMCGadget::<F>::evaluate(params, &[k.k.clone(), r.r.clone(), index.clone()])?;
+        Ok(CiphertextVar { r: r.r.clone(), c: h + &m.m })
+    }
+
+    /// Gadget for [`SymmetricEncryptionScheme::decrypt_block`].
+    pub fn decrypt_block(
+        params: &ParametersVar<F>,
+        k: &SymmetricKeyVar<F>,
+        index: &FpVar<F>,
+        ct: &CiphertextVar<F>,
+    ) -> Result<PlaintextVar<F>, SynthesisError> {
+        let h = MiMCGadget::<F>::evaluate(params, &[k.k.clone(), ct.r.clone(), index.clone()])?;
+        Ok(PlaintextVar { m: &ct.c - h })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -212,6 +241,7 @@ mod tests {
     use ark_r1cs_std::alloc::AllocVar;
     use ark_r1cs_std::eq::EqGadget;
     use ark_r1cs_std::R1CSVar;
+    use ark_r1cs_std::fields::fp::FpVar;
 
     use crate::gadget::{
         hashes::mimc7::{parameters, Parameters, constraints::ParametersVar}, 
@@ -222,7 +252,7 @@ mod tests {
         }
     };
 
-    use super::{Randomness, SymmetricKey, SymmetricEncryptionScheme, Plaintext};
+    use super::{Randomness, SymmetricKey, SymmetricEncryptionScheme, Plaintext, RandomnessVar, SymmetricKeyVar, PlaintextVar};
 
     
     #[test]
@@ -289,4 +319,29 @@ mod tests {
 
         assert!(cs.is_satisfied().unwrap());
     }
+
+    #[test]
+    fn test_block_encryption_gadget() {
+        let rc = Parameters { round_constants: parameters::get_bn256_round_constants().clone() };
+        let random = Randomness { r: Fp::from_str("3").unwrap() };
+        let key = SymmetricKey { k: Fp::from_str("3").unwrap() };
+        let msg = Plaintext::<Fr> { m: Fp::from_str("5").unwrap() };
+
+        let ct = SymmetricEncryptionScheme::encrypt_block(rc.clone(), random.clone(), key.clone(), 7, msg.clone()).unwrap();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let param_var = ParametersVar::<Fr>::new_constant(ark_relations::ns!(cs, "gadget_const"), &rc).unwrap();
+        let randomness_var = RandomnessVar::new_witness(ark_relations::ns!(cs, "gadget_randomness"), || Ok(&random)).unwrap();
+        let key_var = SymmetricKeyVar::new_witness(ark_relations::ns!(cs, "gadget_key"), || Ok(&key)).unwrap();
+        let msg_var = PlaintextVar::new_witness(ark_relations::ns!(cs, "gadget_msg"), || Ok(&msg)).unwrap();
+        let index_var = FpVar::new_input(ark_relations::ns!(cs, "gadget_index"), || Ok(Fr::from(7u64))).unwrap();
+
+        let result_var = SymmetricEncryptionSchemeGadget::encrypt_block(&param_var, &randomness_var, &key_var, &index_var, &msg_var).unwrap();
+        assert_eq!(ct.c, result_var.c.value().unwrap());
+
+        let dec_var = SymmetricEncryptionSchemeGadget::decrypt_block(&param_var, &key_var, &index_var, &result_var).unwrap();
+        dec_var.m.enforce_equal(&msg_var.m).unwrap();
+
+        assert!(cs.is_satisfied().unwrap());
+    }
 }
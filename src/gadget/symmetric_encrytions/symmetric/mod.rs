@@ -81,6 +81,39 @@ where
     }
 }
 
+impl<F> SymmetricEncryptionScheme<F>
+where
+    F: Field + Absorb,
+{
+    /// Encrypts the `index`-th block of a message under the pad `MiMC(k || r || index)`, so that
+    /// blocks sharing a key and randomness are still encrypted under distinct pads.
+    pub fn encrypt_block(
+        params: Parameters<F>,
+        r: Randomness<F>,
+        k: SymmetricKey<F>,
+        index: u64,
+        m: Plaintext<F>,
+    ) -> Result<Ciphertext<F>, Error> {
+        let h = Self::block_pad(&params, &k, r.r, index)?;
+        Ok(Ciphertext { r: r.r, c: h + m.m })
+    }
+
+    /// Decrypts the `index`-th block of a message encrypted with [`Self::encrypt_block`].
+    pub fn decrypt_block(
+        params: Parameters<F>,
+        k: SymmetricKey<F>,
+        index: u64,
+        ct: Ciphertext<F>,
+    ) -> Result<Plaintext<F>, Error> {
+        let h = Self::block_pad(&params, &k, ct.r, index)?;
+        Ok(Plaintext { m: ct.c - h })
+    }
+
+    fn block_pad(params: &Parameters<F>, k: &SymmetricKey<F>, r: F, index: u64) -> Result<F, Error> {
+        mimc7::MiMC::<F>::evaluate(params, [k.k, r, F::from(index)].to_vec())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
@@ -111,4 +144,20 @@ mod tests {
         let m_dec = SymmetricEncryptionScheme::<Fr>::decrypt(rc, key, ct).unwrap();
         println!("m: {:?}", m_dec.m);
     }
+
+    #[test]
+    fn test_block_encryption() {
+        let rc = Parameters { round_constants: parameters::get_bn256_round_constants().clone() };
+        let random = Randomness { r: Fp::from_str("3").unwrap() };
+        let key = SymmetricKey { k: Fp::from_str("3").unwrap() };
+        let msg = Plaintext::<Fr> { m: Fp::from_str("5").unwrap() };
+
+        let ct_0 = SymmetricEncryptionScheme::encrypt_block(rc.clone(), random.clone(), key.clone(), 0, msg.clone()).unwrap();
+        let ct_1 = SymmetricEncryptionScheme::encrypt_block(rc.clone(), random, key.clone(), 1, msg.clone()).unwrap();
+
+        // equal blocks at different indices do not share a pad
+        assert_ne!(ct_0.c, ct_1.c);
+        assert_eq!(SymmetricEncryptionScheme::decrypt_block(rc.clone(), key.clone(), 1, ct_1.clone()).unwrap(), msg);
+        assert_ne!(SymmetricEncryptionScheme::decrypt_block(rc, key, 0, ct_1).unwrap(), msg);
+    }
 }
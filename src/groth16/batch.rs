@@ -0,0 +1,213 @@
+use crate::Error;
+use ark_ec::pairing::Pairing;
+use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField, Zero};
+use ark_groth16::{Groth16, PreparedVerifyingKey, Proof};
+use ark_std::ops::Mul;
+use ark_std::rand::Rng;
+
+/// A proof together with the public inputs it is verified against.
+pub type Instance<E> = (Vec<<E as Pairing>::ScalarField>, Proof<E>);
+
+/// Verifies all `instances` against one verifying key at once.
+///
+/// Each proof must satisfy `e(A, B) = e(alpha, beta) * e(IC, gamma) * e(C, delta)`, where `IC`
+/// combines the public inputs. The equations are combined with random 128-bit weights `r_i` into
+/// `prod e(r_i*A_i, B_i) * e(sum r_i*IC_i, -gamma) * e(sum r_i*C_i, -delta) = e(alpha, beta)^sum r_i`,
+/// which takes one multi-Miller loop with `N + 2` pairs and a single final exponentiation instead
+/// of `N` full verifications. A batch containing an invalid proof passes with probability at most
+/// `2^-128`.
+pub fn batch_verify<E: Pairing, R: Rng>(
+    pvk: &PreparedVerifyingKey<E>,
+    instances: &[Instance<E>],
+    rng: &mut R,
+) -> Result<bool, Error> {
+    if instances.is_empty() {
+        return Ok(true);
+    }
+
+    let weights = instances
+        .iter()
+        .map(|_| E::ScalarField::from(rng.gen::<u128>()))
+        .collect::<Vec<_>>();
+
+    let prepared_inputs = instances
+        .iter()
+        .map(|(inputs, _)| Groth16::<E>::prepare_inputs(pvk, inputs))
+        .collect::<Result<Vec<_>, _>>()?;
+    let combined_inputs = E::G1::msm(&E::G1::normalize_batch(&prepared_inputs), &weights)
+        .map_err(|_| "mismatched number of bases and weights")?;
+    let combined_c = E::G1::msm(
+        &instances
+            .iter()
+            .map(|(_, proof)| proof.c)
+            .collect::<Vec<_>>(),
+        &weights,
+    )
+    .map_err(|_| "mismatched number of bases and weights")?;
+
+    let mut g1 = instances
+        .iter()
+        .zip(weights.iter())
+        .map(|((_, proof), r)| proof.a.mul(r))
+        .collect::<Vec<_>>();
+    g1.push(combined_inputs);
+    g1.push(combined_c);
+    let g1 = E::G1::normalize_batch(&g1);
+
+    let mut g2 = instances
+        .iter()
+        .map(|(_, proof)| proof.b.into())
+        .collect::<Vec<E::G2Prepared>>();
+    g2.push(pvk.gamma_g2_neg_pc.clone());
+    g2.push(pvk.delta_g2_neg_pc.clone());
+
+    let product = E::final_exponentiation(E::multi_miller_loop(g1, g2))
+        .ok_or("final exponentiation failed")?;
+
+    let weight_sum = weights
+        .iter()
+        .fold(E::ScalarField::zero(), |sum, r| sum + r);
+    Ok(product.0 == pvk.alpha_g1_beta_g2.pow(weight_sum.into_bigint()))
+}
+
+/// Returns the indices of the `instances` that fail verification, in increasing order.
+///
+/// Instances with the wrong number of public inputs for `pvk` are reported as invalid up front.
+/// The other ones are checked with [`batch_verify`] as a whole first; only a failing batch is
+/// bisected, so locating `k` invalid proofs among `N` takes `O(k log N)` batch verifications.
+pub fn find_invalid<E: Pairing, R: Rng>(
+    pvk: &PreparedVerifyingKey<E>,
+    instances: &[Instance<E>],
+    rng: &mut R,
+) -> Result<Vec<usize>, Error> {
+    let (well_formed, mut invalid): (Vec<usize>, Vec<usize>) =
+        (0..instances.len()).partition(|&i| instances[i].0.len() + 1 == pvk.vk.gamma_abc_g1.len());
+    let well_formed_instances = well_formed
+        .iter()
+        .map(|&i| instances[i].clone())
+        .collect::<Vec<_>>();
+
+    let mut failed = Vec::new();
+    bisect(pvk, &well_formed_instances, 0, &mut failed, rng)?;
+    invalid.extend(failed.into_iter().map(|i| well_formed[i]));
+    invalid.sort_unstable();
+    Ok(invalid)
+}
+
+fn bisect<E: Pairing, R: Rng>(
+    pvk: &PreparedVerifyingKey<E>,
+    instances: &[Instance<E>],
+    offset: usize,
+    invalid: &mut Vec<usize>,
+    rng: &mut R,
+) -> Result<(), Error> {
+    if batch_verify(pvk, instances, rng)? {
+        return Ok(());
+    }
+    if instances.len() == 1 {
+        invalid.push(offset);
+        return Ok(());
+    }
+
+    let mid = instances.len() / 2;
+    bisect(pvk, &instances[..mid], offset, invalid, rng)?;
+    bisect(pvk, &instances[mid..], offset + mid, invalid, rng)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_groth16::Groth16;
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::{batch_verify, find_invalid, Instance};
+    use crate::datatrade::circuit::generate_test_input;
+
+    type F = ark_bn254::Fr;
+
+    pub(crate) fn generate_registration_proofs(
+        n: usize,
+    ) -> (
+        ark_groth16::PreparedVerifyingKey<Bn254>,
+        Vec<Instance<Bn254>>,
+    ) {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let input = generate_test_input(2).unwrap();
+        let (pk, vk) = Groth16::<Bn254>::setup(input.clone(), &mut rng).unwrap();
+
+        let pk_peer_own = input.pk_peer_own.unwrap();
+        let image = vec![
+            input.h_k_data.unwrap(),
+            pk_peer_own.x,
+            pk_peer_own.y,
+            input.h_ct.unwrap(),
+        ];
+        let instances = (0..n)
+            .map(|_| {
+                let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+                (image.clone(), proof)
+            })
+            .collect();
+        (Groth16::<Bn254>::process_vk(&vk).unwrap(), instances)
+    }
+
+    #[test]
+    fn test_batch_verify() {
+        let rng = &mut test_rng();
+        let (pvk, instances) = generate_registration_proofs(32);
+
+        assert!(batch_verify(&pvk, &instances, rng).unwrap());
+        assert!(batch_verify(&pvk, &instances[..1], rng).unwrap());
+        assert!(batch_verify::<Bn254, _>(&pvk, &[], rng).unwrap());
+
+        // one proof with a wrong public input spoils the batch
+        let mut tampered = instances.clone();
+        tampered[17].0[0] = F::rand(rng);
+        assert!(!batch_verify(&pvk, &tampered, rng).unwrap());
+
+        // as does one proof against the public inputs of no statement
+        let mut tampered = instances.clone();
+        tampered.swap(3, 4);
+        tampered[3].1.c = instances[0].1.a;
+        assert!(!batch_verify(&pvk, &tampered, rng).unwrap());
+
+        // public inputs of the wrong length are an error
+        let mut tampered = instances;
+        tampered[0].0.pop();
+        assert!(batch_verify(&pvk, &tampered, rng).is_err());
+    }
+
+    #[test]
+    fn test_find_invalid() {
+        let rng = &mut test_rng();
+        let (pvk, mut instances) = generate_registration_proofs(24);
+        assert!(find_invalid(&pvk, &instances, rng).unwrap().is_empty());
+
+        for i in [0, 9, 10, 23] {
+            instances[i].0[3] = F::rand(rng);
+        }
+        assert_eq!(
+            find_invalid(&pvk, &instances, rng).unwrap(),
+            vec![0, 9, 10, 23]
+        );
+    }
+
+    #[test]
+    fn test_find_invalid_with_malformed_instance() {
+        let rng = &mut test_rng();
+        let (pvk, mut instances) = generate_registration_proofs(8);
+
+        // a malformed instance is reported without aborting the search for the others
+        instances[2].0.pop();
+        instances[5].0[3] = F::rand(rng);
+        assert_eq!(find_invalid(&pvk, &instances, rng).unwrap(), vec![2, 5]);
+
+        instances[5].0.push(F::rand(rng));
+        assert_eq!(find_invalid(&pvk, &instances, rng).unwrap(), vec![2, 5]);
+    }
+}
@@ -0,0 +1,5 @@
+//! Helpers around Groth16 proofs that are independent of the circuit, for the backend verifying
+//! many registration proofs.
+
+pub mod batch;
+pub mod rerandomize;
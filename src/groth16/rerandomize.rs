@@ -0,0 +1,65 @@
+use ark_ec::pairing::Pairing;
+use ark_groth16::{Groth16, Proof, VerifyingKey};
+use ark_std::rand::Rng;
+
+/// Returns a fresh proof of the same statement, valid for `vk` and the same public inputs as
+/// `proof`, so that a relayer can repost a proof without it being linkable to the original.
+///
+/// The proof elements are rescaled as `A' = A/r1`, `B' = r1*B + r1*r2*delta`,
+/// `C' = C + r2*A`, which preserves the verification equation; the result is distributed like
+/// a freshly generated proof, and is valid only if `proof` is.
+pub fn rerandomize<E: Pairing, R: Rng>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    rng: &mut R,
+) -> Proof<E> {
+    Groth16::<E>::rerandomize_proof(vk, proof, rng)
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Bn254;
+    use ark_crypto_primitives::snark::{CircuitSpecificSetupSNARK, SNARK};
+    use ark_groth16::Groth16;
+    use ark_std::{
+        rand::{RngCore, SeedableRng},
+        test_rng, UniformRand,
+    };
+
+    use super::rerandomize;
+    use crate::datatrade::circuit::generate_test_input;
+
+    type F = ark_bn254::Fr;
+
+    #[test]
+    fn test_rerandomize() {
+        let mut rng = ark_std::rand::rngs::StdRng::seed_from_u64(test_rng().next_u64());
+        let input = generate_test_input(2).unwrap();
+        let (pk, vk) = Groth16::<Bn254>::setup(input.clone(), &mut rng).unwrap();
+        let proof = Groth16::<Bn254>::prove(&pk, input.clone(), &mut rng).unwrap();
+
+        let pk_peer_own = input.pk_peer_own.unwrap();
+        let image = vec![
+            input.h_k_data.unwrap(),
+            pk_peer_own.x,
+            pk_peer_own.y,
+            input.h_ct.unwrap(),
+        ];
+
+        let rerandomized = rerandomize(&vk, &proof, &mut rng);
+        assert!(Groth16::<Bn254>::verify(&vk, &image, &rerandomized).unwrap());
+        assert_ne!(rerandomized.a, proof.a);
+        assert_ne!(rerandomized.b, proof.b);
+        assert_ne!(rerandomized.c, proof.c);
+
+        // rerandomizing again gives yet another valid proof
+        let again = rerandomize(&vk, &rerandomized, &mut rng);
+        assert!(Groth16::<Bn254>::verify(&vk, &image, &again).unwrap());
+        assert_ne!(again, rerandomized);
+
+        // but does not make the proof valid for other public inputs
+        let mut other_image = image;
+        other_image[0] = F::rand(&mut rng);
+        assert!(!Groth16::<Bn254>::verify(&vk, &other_image, &again).unwrap());
+    }
+}
@@ -1,13 +1,24 @@
 pub type Error = Box<dyn ark_std::error::Error>;
 
 pub mod datatrade;
+pub mod encoding;
 pub mod gadget;
+pub mod groth16;
 
 use crate::datatrade::circuit::cat;
 use crate::datatrade::circuit::test_data;
+use crate::gadget::public_encryptions::elgamal::{ElGamal, Parameters};
+use crate::gadget::public_encryptions::AsymmetricEncryptionScheme;
+use ark_bn254::Bn254;
+use ark_ed_on_bn254::{EdwardsAffine, EdwardsProjective};
+use ark_groth16::VerifyingKey;
+use ark_std::rand::rngs::OsRng;
 use cocoa::base::nil;
 use cocoa::foundation::NSString;
+use json_writer::JSONObjectWriter;
+use std::ffi::CStr;
 use std::ffi::CString;
+use std::os::raw::c_char;
 #[no_mangle]
 pub extern "C" fn add_numbers(a: i32, b: i32) -> i32 {
     // test_Data();
@@ -31,6 +42,154 @@ pub extern "C" fn test_circuit() -> CString {
     c_string
 }
 
+/// Reads a NUL-terminated UTF-8 string passed over FFI.
+unsafe fn read_c_str<'a>(s: *const c_char) -> Option<&'a str> {
+    if s.is_null() {
+        return None;
+    }
+    CStr::from_ptr(s).to_str().ok()
+}
+
+/// Hands a string over FFI; the caller releases it with [`free_string`]. Errors become NULL.
+fn into_c_string(s: Result<String, Error>) -> *mut c_char {
+    s.ok()
+        .and_then(|s| CString::new(s).ok())
+        .map_or(std::ptr::null_mut(), CString::into_raw)
+}
+
+/// Releases a string returned by this library.
+///
+/// # Safety
+///
+/// `s` must be NULL or a pointer returned by this library that has not been released yet; it
+/// must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// Returns hex-encoded ElGamal parameters over ed-on-bn254.
+#[no_mangle]
+pub extern "C" fn elgamal_setup() -> *mut c_char {
+    into_c_string(
+        ElGamal::<EdwardsProjective>::setup(&mut OsRng).and_then(|pp| encoding::to_hex(&pp)),
+    )
+}
+
+/// Generates a key pair for hex-encoded parameters and returns `{"pk": hex, "sk": hex}`.
+///
+/// # Safety
+///
+/// `parameters_hex` must be NULL or point to a NUL-terminated string that stays valid for the
+/// call. The returned string is owned by the caller and must be released with [`free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn elgamal_keygen(parameters_hex: *const c_char) -> *mut c_char {
+    into_c_string((|| {
+        let pp: Parameters<EdwardsProjective> =
+            encoding::from_hex(read_c_str(parameters_hex).ok_or("invalid parameters")?)?;
+        let (pk, sk) = ElGamal::<EdwardsProjective>::keygen(&pp, &mut OsRng)?;
+
+        let mut object_str = String::new();
+        let mut object_writer = JSONObjectWriter::new(&mut object_str);
+        object_writer.value("pk", encoding::to_hex(&pk)?.as_str());
+        object_writer.value("sk", encoding::to_hex(&sk)?.as_str());
+        object_writer.end();
+        Ok(object_str)
+    })())
+}
+
+/// Returns whether `pk_hex` encodes a valid ed-on-bn254 public key.
+///
+/// # Safety
+///
+/// `pk_hex` must be NULL or point to a NUL-terminated string that stays valid for the call.
+#[no_mangle]
+pub unsafe extern "C" fn elgamal_public_key_is_valid(pk_hex: *const c_char) -> bool {
+    read_c_str(pk_hex).is_some_and(|pk| encoding::from_hex::<EdwardsAffine>(pk).is_ok())
+}
+
+/// Re-encodes a hex string as base64, e.g. for storage in the keychain.
+///
+/// # Safety
+///
+/// `s` must be NULL or point to a NUL-terminated string that stays valid for the call. The
+/// returned string is owned by the caller and must be released with [`free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn hex_to_base64(s: *const c_char) -> *mut c_char {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    into_c_string((|| {
+        let bytes = hex::decode(read_c_str(s).ok_or("invalid string")?)?;
+        Ok(STANDARD.encode(bytes))
+    })())
+}
+
+/// Re-encodes a base64 string as hex.
+///
+/// # Safety
+///
+/// `s` must be NULL or point to a NUL-terminated string that stays valid for the call. The
+/// returned string is owned by the caller and must be released with [`free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn base64_to_hex(s: *const c_char) -> *mut c_char {
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    into_c_string((|| {
+        let bytes = STANDARD.decode(read_c_str(s).ok_or("invalid string")?)?;
+        Ok(hex::encode(bytes))
+    })())
+}
+
+/// Verifies Groth16 proofs over BN254 sharing the hex-encoded verifying key `vk_hex`.
+///
+/// `instances_json` is an array of `{"inputs": [hex, ...], "proof": hex}`. Returns
+/// `{"valid": bool, "invalid": [index, ...]}` listing the proofs that fail verification,
+/// including instances with the wrong number of public inputs.
+///
+/// # Safety
+///
+/// `vk_hex` and `instances_json` must each be NULL or point to a NUL-terminated string that stays
+/// valid for the call; their length is given by the terminator. The returned string is owned by
+/// the caller and must be released with [`free_string`].
+#[no_mangle]
+pub unsafe extern "C" fn groth16_batch_verify(
+    vk_hex: *const c_char,
+    instances_json: *const c_char,
+) -> *mut c_char {
+    into_c_string((|| {
+        let vk: VerifyingKey<Bn254> =
+            encoding::from_hex(read_c_str(vk_hex).ok_or("invalid verifying key")?)?;
+        let parsed = json::parse(read_c_str(instances_json).ok_or("invalid instances")?)?;
+        let instances = parsed
+            .members()
+            .map(|instance| {
+                let inputs = instance["inputs"]
+                    .members()
+                    .map(|input| encoding::from_hex(input.as_str().ok_or("invalid input")?))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                let proof = encoding::from_hex(instance["proof"].as_str().ok_or("invalid proof")?)?;
+                Ok((inputs, proof))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        let pvk = ark_groth16::prepare_verifying_key(&vk);
+        let invalid = groth16::batch::find_invalid(&pvk, &instances, &mut OsRng)?;
+
+        let mut object_str = String::new();
+        let mut object_writer = JSONObjectWriter::new(&mut object_str);
+        object_writer.value("valid", invalid.is_empty());
+        let mut invalid_writer = object_writer.array("invalid");
+        for i in invalid {
+            invalid_writer.value(i as u32);
+        }
+        invalid_writer.end();
+        object_writer.end();
+        Ok(object_str)
+    })())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,4 +204,62 @@ mod tests {
         let result = add_numbers(2, 2);
         println!("{:?}", result);
     }
+
+    #[test]
+    fn elgamal_ffi() {
+        unsafe {
+            let pp = elgamal_setup();
+            assert!(!pp.is_null());
+
+            let keys = elgamal_keygen(pp);
+            let parsed = json::parse(CStr::from_ptr(keys).to_str().unwrap()).unwrap();
+            let pk = CString::new(parsed["pk"].as_str().unwrap()).unwrap();
+            assert!(elgamal_public_key_is_valid(pk.as_ptr()));
+
+            let pk_base64 = hex_to_base64(pk.as_ptr());
+            let pk_hex = base64_to_hex(pk_base64);
+            assert_eq!(CStr::from_ptr(pk_hex), pk.as_c_str());
+
+            let invalid = CString::new("00").unwrap();
+            assert!(!elgamal_public_key_is_valid(invalid.as_ptr()));
+            assert!(elgamal_keygen(invalid.as_ptr()).is_null());
+
+            for s in [pp, keys, pk_base64, pk_hex] {
+                free_string(s);
+            }
+        }
+    }
+
+    #[test]
+    fn groth16_batch_verify_ffi() {
+        let (pvk, mut instances) = groth16::batch::tests::generate_registration_proofs(3);
+        instances[1].0[0] = ark_bn254::Fr::from(1u64);
+        instances[2].0.pop();
+
+        let mut instances_json = json::JsonValue::new_array();
+        for (inputs, proof) in instances.iter() {
+            let inputs = inputs
+                .iter()
+                .map(|input| encoding::to_hex(input).unwrap())
+                .collect::<Vec<_>>();
+            let mut instance = json::JsonValue::new_object();
+            instance["inputs"] = inputs.into();
+            instance["proof"] = encoding::to_hex(proof).unwrap().into();
+            instances_json.push(instance).unwrap();
+        }
+
+        unsafe {
+            let vk = CString::new(encoding::to_hex(&pvk.vk).unwrap()).unwrap();
+            let instances_json = CString::new(instances_json.dump()).unwrap();
+            let result = groth16_batch_verify(vk.as_ptr(), instances_json.as_ptr());
+            let parsed = json::parse(CStr::from_ptr(result).to_str().unwrap()).unwrap();
+            assert_eq!(parsed["valid"].as_bool(), Some(false));
+            assert_eq!(parsed["invalid"], json::array![1, 2]);
+
+            let invalid = CString::new("[{}]").unwrap();
+            assert!(groth16_batch_verify(vk.as_ptr(), invalid.as_ptr()).is_null());
+
+            free_string(result);
+        }
+    }
 }